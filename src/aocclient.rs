@@ -1,6 +1,13 @@
+//! A client for the Advent of Code website.
+
 use std::collections::HashMap;
 use std::error;
 use std::fmt;
+use std::fs;
+use std::io;
+use std::path::Path;
+use std::path::PathBuf;
+use std::process;
 use std::sync::Arc;
 use std::sync::LazyLock;
 use std::sync::RwLock;
@@ -9,8 +16,17 @@ use std::time::Duration;
 use aho_corasick::AhoCorasick;
 use http::HeaderValue;
 use log::debug;
+use log::warn;
 use regex::Regex;
+use reqwest::StatusCode;
 use reqwest::Url;
+use reqwest::blocking::Response;
+use serde::Serialize;
+
+use crate::leaderboard::Leaderboard;
+use crate::puzzle;
+use crate::puzzle::Example;
+use crate::puzzle::Examples;
 
 /// Implements a CookieStore for the sole purpose of transmitting the Advent of Code
 /// session cookie. Will not store any other cookies.
@@ -47,43 +63,122 @@ impl reqwest::cookie::CookieStore for AocSessionCookieStore {
     }
 }
 
+/// An error talking to the Advent of Code website. Each kind of error calls
+/// for something different to be done about it, which its message suggests.
 #[derive(Debug)]
 pub enum Error {
-    HttpError(reqwest::Error),
+    /// The session cookie is missing or has expired.
+    NotLoggedIn,
+    /// The puzzle of the given day has not been unlocked yet.
+    PuzzleLocked { year: i16, day: i8 },
+    /// The server failed to handle the request.
+    ServerError(StatusCode),
+    /// The server could not be reached, or the connection failed.
+    Transport(reqwest::Error),
+    /// The server answered, but not with what was asked for.
+    UnexpectedResponse(String),
 }
 
 impl fmt::Display for Error {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(f, "error") // TODO
+        match self {
+            Self::NotLoggedIn => write!(
+                f,
+                "not logged in to Advent of Code: the session cookie is missing or has expired; \
+                 copy the session cookie from your browser to --cookie, AOC_COOKIE or \
+                 session_cookie in aoc.toml"
+            ),
+            Self::PuzzleLocked { year, day } => write!(
+                f,
+                "day {day} of {year} is not unlocked yet: puzzles unlock at midnight EST \
+                 (05:00 UTC), check the year and day"
+            ),
+            Self::ServerError(status) => write!(
+                f,
+                "the Advent of Code server failed with {status}: it may be overloaded, \
+                 try again in a few minutes"
+            ),
+            Self::Transport(e) => write!(
+                f,
+                "cannot reach the Advent of Code server: {e}; check your network connection \
+                 and the base URL"
+            ),
+            Self::UnexpectedResponse(message) => {
+                write!(
+                    f,
+                    "unexpected answer from the Advent of Code server: {message}"
+                )
+            }
+        }
     }
 }
 
 impl error::Error for Error {
     fn source(&self) -> Option<&(dyn error::Error + 'static)> {
         match *self {
-            Self::HttpError(ref e) => Some(e),
+            Self::Transport(ref e) => Some(e),
+            Self::NotLoggedIn
+            | Self::PuzzleLocked { .. }
+            | Self::ServerError(_)
+            | Self::UnexpectedResponse(_) => None,
         }
     }
 }
 
 impl From<reqwest::Error> for Error {
     fn from(e: reqwest::Error) -> Error {
-        Error::HttpError(e)
+        Error::Transport(e)
+    }
+}
+
+/// Returns the response if it is successful, or else the matching error.
+/// `not_found` is the error for a 404, which means different things for
+/// different pages.
+fn check_response<F: FnOnce() -> Error>(
+    response: Response,
+    not_found: F,
+) -> Result<Response, Error> {
+    let status = response.status();
+    if status.is_success() {
+        return Ok(response);
+    }
+    // The server asks to log in with a 400 or a 500, depending on the page.
+    let body = response.text().unwrap_or_default();
+    if body.contains("Please log in") {
+        return Err(Error::NotLoggedIn);
     }
+    Err(match status {
+        StatusCode::NOT_FOUND => not_found(),
+        status if status.is_server_error() => Error::ServerError(status),
+        status => Error::UnexpectedResponse(format!("the request failed with {status}")),
+    })
 }
 
+const DEFAULT_BASE_URL: &str = "https://adventofcode.com/";
+
+/// How long to keep a private leaderboard before downloading it again. The
+/// website asks for at least 15 minutes.
+const LEADERBOARD_CACHE_TIME: Duration = Duration::from_secs(15 * 60);
+
+/// A client for the Advent of Code website, which downloads puzzle inputs
+/// and submits answers on behalf of the user whose session cookie it has.
 pub struct AocClient {
     base_url: String,
     cookie_store: Arc<AocSessionCookieStore>,
     client: reqwest::blocking::Client,
+    input_cache: Option<PathBuf>,
+    refresh: bool,
 }
 
-#[derive(Debug, PartialEq)]
+/// What the server said about a submitted answer.
+#[derive(Debug, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
 pub enum ValidationResult {
     Accepted,
     Rejected,
     RejectedTooLow,
     RejectedTooHigh,
+    /// An answer was submitted too recently. Try again after this long.
     Throttled(Duration),
 }
 
@@ -133,7 +228,23 @@ fn parse_validation_response(text: &str) -> ValidationResult {
     result
 }
 
+/// Writes `contents` to a temporary file next to `path`, then renames it, so
+/// that `path` never holds part of the contents.
+fn write_atomically(path: &Path, contents: &str) -> io::Result<()> {
+    let dir = path.parent().expect("cache file has a directory");
+    fs::create_dir_all(dir)?;
+    let mut temp_name = path.file_name().unwrap().to_os_string();
+    temp_name.push(format!(".{}.tmp", process::id()));
+    let temp_path = dir.join(temp_name);
+    fs::write(&temp_path, contents)?;
+    fs::rename(&temp_path, path).inspect_err(|_| {
+        let _ = fs::remove_file(&temp_path);
+    })
+}
+
 impl AocClient {
+    /// Creates a client for a server other than adventofcode.com, such as a
+    /// test server.
     pub fn new_with_base(base_url: &str) -> Result<AocClient, Error> {
         // We need to use an Arc here because reqwest::ClientBuilder requires an
         // Arc<C> of CookieStore:
@@ -147,29 +258,297 @@ impl AocClient {
             base_url: String::from(base_url),
             cookie_store,
             client,
+            input_cache: None,
+            refresh: false,
         })
     }
 
+    /// Creates a client for adventofcode.com.
     pub fn new() -> Result<AocClient, Error> {
-        Self::new_with_base("https://adventofcode.com/")
+        Self::new_with_base(DEFAULT_BASE_URL)
+    }
+
+    /// Keeps downloaded puzzle inputs in `dir`, as `{year}/day{NN}.txt`, and
+    /// reads them from there instead of downloading them again.
+    pub fn with_input_cache(mut self, dir: PathBuf) -> AocClient {
+        self.input_cache = Some(dir);
+        self
+    }
+
+    /// Downloads puzzle inputs even if they are in the cache, and replaces the
+    /// cached copies.
+    pub fn with_refresh(mut self) -> AocClient {
+        self.refresh = true;
+        self
     }
 
-    pub fn get_puzzle_input(&self, day: i8) -> Result<String, Error> {
-        let url = self.base_url.clone() + format!("2018/day/{0}/input", day).as_str();
+    /// Returns where to cache a file about the given day, such as the puzzle
+    /// input with the extension "txt".
+    fn cache_path(&self, year: i16, day: i8, extension: &str) -> Option<PathBuf> {
+        let dir = self.input_cache.as_ref()?;
+        Some(
+            dir.join(year.to_string())
+                .join(format!("day{day:02}.{extension}")),
+        )
+    }
+
+    /// Whether the puzzle input of the given day would be read from the cache
+    /// rather than downloaded.
+    pub fn has_cached_input(&self, year: i16, day: i8) -> bool {
+        !self.refresh
+            && self
+                .cache_path(year, day, "txt")
+                .is_some_and(|path| path.is_file())
+    }
+
+    /// Returns the puzzle input of the given day, from the cache if it is
+    /// there, or else downloaded from the server.
+    pub fn get_puzzle_input(&self, year: i16, day: i8) -> Result<String, Error> {
+        let path = self.cache_path(year, day, "txt");
+        if let Some(path) = &path
+            && !self.refresh
+        {
+            match fs::read_to_string(path) {
+                Ok(input) => {
+                    debug!("read puzzle input from {}", path.display());
+                    return Ok(input);
+                }
+                Err(e) if e.kind() == io::ErrorKind::NotFound => {}
+                Err(e) => warn!("cannot read cached puzzle input {}: {e}", path.display()),
+            }
+        }
+        let input = self.download_puzzle_input(year, day)?;
+        if let Some(path) = &path {
+            // Puzzle inputs always end with a newline, so one that does not has
+            // most likely been cut short.
+            if !input.ends_with('\n') {
+                warn!("not caching puzzle input that does not end with a newline");
+            } else if let Err(e) = write_atomically(path, &input) {
+                warn!("cannot cache puzzle input in {}: {e}", path.display());
+            }
+        }
+        Ok(input)
+    }
+
+    /// Downloads the puzzle input of the given day.
+    fn download_puzzle_input(&self, year: i16, day: i8) -> Result<String, Error> {
+        let url = self.base_url.clone() + format!("{year}/day/{day}/input").as_str();
         let response = self.client.get(url).send()?;
-        match response.error_for_status() {
-            Ok(response) => Ok(response.text()?),
-            Err(e) => Err(Error::HttpError(e)),
+        let response = check_response(response, || Error::PuzzleLocked { year, day })?;
+        let expected_len = response.content_length();
+        let input = response.text()?;
+        if let Some(expected_len) = expected_len
+            && input.len() as u64 != expected_len
+        {
+            return Err(Error::UnexpectedResponse(format!(
+                "the puzzle input was cut short after {} of {expected_len} bytes",
+                input.len()
+            )));
+        }
+        if input.trim_start().starts_with('<') {
+            return Err(Error::UnexpectedResponse(
+                "the server sent a web page instead of the puzzle input".into(),
+            ));
         }
+        Ok(input)
     }
 
+    /// Returns the description of the given day's puzzle as Markdown,
+    /// including part two once it is unlocked.
+    ///
+    /// Descriptions are cached next to the puzzle inputs. A cached
+    /// description without part two is downloaded again, unless the server
+    /// cannot be reached.
+    pub fn get_puzzle_description(&self, year: i16, day: i8) -> Result<String, Error> {
+        self.get_puzzle_page_file(
+            year,
+            day,
+            "md",
+            |description| description.contains(puzzle::PART_TWO_HEADING),
+            |html, page_url| {
+                puzzle::description_markdown(html, page_url).ok_or_else(|| {
+                    Error::UnexpectedResponse("the puzzle page has no puzzle description".into())
+                })
+            },
+        )
+    }
+
+    /// Returns the examples in the given day's puzzle description, including
+    /// those of part two once it is unlocked.
+    ///
+    /// Examples are cached next to the puzzle inputs, as JSON that can be
+    /// edited to fix what [`puzzle::examples`] gets wrong. Examples cached
+    /// before part two was unlocked are downloaded again, unless the server
+    /// cannot be reached.
+    pub fn get_puzzle_examples(&self, year: i16, day: i8) -> Result<Vec<Example>, Error> {
+        let json = self.get_puzzle_page_file(
+            year,
+            day,
+            "examples.json",
+            |json| serde_json::from_str::<Examples>(json).is_ok_and(|e| e.part_two_unlocked),
+            |html, _| {
+                let examples = puzzle::examples(html);
+                Ok(serde_json::to_string_pretty(&examples).expect("serializing examples") + "\n")
+            },
+        )?;
+        serde_json::from_str::<Examples>(&json)
+            .map(|examples| examples.examples)
+            .map_err(|e| {
+                Error::UnexpectedResponse(format!("the cached examples are not valid: {e}"))
+            })
+    }
+
+    /// Returns a file made from the given day's puzzle page with `convert`,
+    /// from the cache if it is `complete` there, or else downloaded again.
+    fn get_puzzle_page_file<C, F>(
+        &self,
+        year: i16,
+        day: i8,
+        extension: &str,
+        complete: C,
+        convert: F,
+    ) -> Result<String, Error>
+    where
+        C: Fn(&str) -> bool,
+        F: Fn(&str, &Url) -> Result<String, Error>,
+    {
+        let path = self.cache_path(year, day, extension);
+        let cached = match &path {
+            Some(path) if !self.refresh => match fs::read_to_string(path) {
+                Ok(contents) => Some(contents),
+                Err(e) if e.kind() == io::ErrorKind::NotFound => None,
+                Err(e) => {
+                    warn!("cannot read cached {}: {e}", path.display());
+                    None
+                }
+            },
+            _ => None,
+        };
+        if let Some(contents) = &cached
+            && complete(contents)
+        {
+            return Ok(contents.clone());
+        }
+        let contents = match self
+            .download_puzzle_page(year, day)
+            .and_then(|(html, page_url)| convert(&html, &page_url))
+        {
+            Ok(contents) => contents,
+            Err(e) => {
+                return match cached {
+                    Some(contents) => {
+                        warn!("using the cached copy, which may be missing part two: {e}");
+                        Ok(contents)
+                    }
+                    None => Err(e),
+                };
+            }
+        };
+        if let Some(path) = &path
+            && let Err(e) = write_atomically(path, &contents)
+        {
+            warn!("cannot cache {}: {e}", path.display());
+        }
+        Ok(contents)
+    }
+
+    /// Downloads the puzzle page of the given day, and returns it with its
+    /// URL.
+    fn download_puzzle_page(&self, year: i16, day: i8) -> Result<(String, Url), Error> {
+        let url = self.base_url.clone() + format!("{year}/day/{day}").as_str();
+        let response = self.client.get(&url).send()?;
+        let response = check_response(response, || Error::PuzzleLocked { year, day })?;
+        let page_url = response.url().clone();
+        Ok((response.text()?, page_url))
+    }
+
+    /// Returns the private leaderboard with the given id.
+    ///
+    /// The website asks not to fetch a leaderboard more than once every 15
+    /// minutes, so leaderboards are cached next to the puzzle inputs for that
+    /// long, even with [`AocClient::with_refresh`]. A stale copy is used if
+    /// the server cannot be reached.
+    pub fn get_private_leaderboard(&self, year: i16, id: u64) -> Result<Leaderboard, Error> {
+        let path = self.input_cache.as_ref().map(|dir| {
+            dir.join(year.to_string())
+                .join(format!("leaderboard-{id}.json"))
+        });
+        let cached = path.as_ref().and_then(|path| {
+            let age = fs::metadata(path)
+                .and_then(|metadata| metadata.modified())
+                .ok()?
+                .elapsed()
+                .unwrap_or_default();
+            match fs::read_to_string(path).map(|json| serde_json::from_str::<Leaderboard>(&json)) {
+                Ok(Ok(leaderboard)) => Some((leaderboard, age)),
+                Ok(Err(e)) => {
+                    warn!(
+                        "ignoring invalid cached leaderboard {}: {e}",
+                        path.display()
+                    );
+                    None
+                }
+                Err(e) => {
+                    warn!("cannot read cached leaderboard {}: {e}", path.display());
+                    None
+                }
+            }
+        });
+        if let Some((leaderboard, age)) = &cached
+            && *age < LEADERBOARD_CACHE_TIME
+        {
+            debug!("using leaderboard cached {age:?} ago");
+            return Ok(leaderboard.clone());
+        }
+        let json = match self.download_private_leaderboard(year, id) {
+            Ok(json) => json,
+            Err(e) => {
+                return match cached {
+                    Some((leaderboard, age)) => {
+                        warn!("using the leaderboard cached {age:?} ago: {e}");
+                        Ok(leaderboard)
+                    }
+                    None => Err(e),
+                };
+            }
+        };
+        let leaderboard = serde_json::from_str(&json)
+            .map_err(|e| Error::UnexpectedResponse(format!("the leaderboard is not valid: {e}")))?;
+        if let Some(path) = &path
+            && let Err(e) = write_atomically(path, &json)
+        {
+            warn!("cannot cache leaderboard in {}: {e}", path.display());
+        }
+        Ok(leaderboard)
+    }
+
+    fn download_private_leaderboard(&self, year: i16, id: u64) -> Result<String, Error> {
+        let url =
+            self.base_url.clone() + format!("{year}/leaderboard/private/view/{id}.json").as_str();
+        let response = self.client.get(url).send()?;
+        let response = check_response(response, || {
+            Error::UnexpectedResponse(format!("there is no private leaderboard {id} for {year}"))
+        })?;
+        let json = response.text()?;
+        // Those who cannot see the leaderboard are sent to a web page instead.
+        if json.trim_start().starts_with('<') {
+            return Err(Error::UnexpectedResponse(format!(
+                "the server sent a web page instead of leaderboard {id}: check that you are \
+                 logged in as one of its members"
+            )));
+        }
+        Ok(json)
+    }
+
+    /// Submits the answer to part `level` of the given day.
     pub fn submit_answer(
         &self,
+        year: i16,
         day: i8,
         level: i8,
         answer: &str,
     ) -> Result<ValidationResult, Error> {
-        let url = self.base_url.clone() + format!("2018/day/{0}/answer", day).as_str();
+        let url = self.base_url.clone() + format!("{year}/day/{day}/answer").as_str();
         let request = self
             .client
             .post(url)
@@ -179,12 +558,11 @@ impl AocClient {
             ]))
             .build()?;
         let response = self.client.execute(request)?;
-        match response.error_for_status() {
-            Ok(response) => Ok(parse_validation_response(response.text()?.as_str())),
-            Err(e) => Err(Error::HttpError(e)),
-        }
+        let response = check_response(response, || Error::PuzzleLocked { year, day })?;
+        Ok(parse_validation_response(response.text()?.as_str()))
     }
 
+    /// Sets the session cookie used to log in.
     pub fn set_cookie(&self, cookie: &str) {
         *self.cookie_store.cookie.write().unwrap() = Some(String::from(cookie));
     }
@@ -31,7 +31,10 @@ fn test_get_input_success() {
     let client =
         AocClient::new_with_base(server_url(&server).as_str()).expect("creating AoC client");
     client.set_cookie("deadbeef");
-    assert_eq!(client.get_puzzle_input(19).expect("getting input"), "");
+    assert_eq!(
+        client.get_puzzle_input(2018, 19).expect("getting input"),
+        ""
+    );
 }
 
 fn submit_answer_test(body: &'static str, expected_result: ValidationResult) {
@@ -49,7 +52,7 @@ fn submit_answer_test(body: &'static str, expected_result: ValidationResult) {
     client.set_cookie("deadbeef");
     assert_eq!(
         client
-            .submit_answer(19, 1, "THE ANSWER")
+            .submit_answer(2018, 19, 1, "THE ANSWER")
             .expect("getting input"),
         expected_result
     );
@@ -102,3 +105,312 @@ fn test_submit_answer_throttled_with_minutes() {
         ValidationResult::Throttled(Duration::from_secs(331)),
     );
 }
+
+#[test]
+fn test_get_input_other_year() {
+    let server = SERVER_POOL.get_server();
+    let m = all_of![
+        request::method("GET"),
+        request::path(matches("/2023/day/7/input")),
+    ];
+    server.expect(Expectation::matching(m).respond_with(status_code(200).body("input")));
+    let client =
+        AocClient::new_with_base(server_url(&server).as_str()).expect("creating AoC client");
+    client.set_cookie("deadbeef");
+    assert_eq!(
+        client.get_puzzle_input(2023, 7).expect("getting input"),
+        "input"
+    );
+}
+
+fn cached_client(server: &Server, dir: &Path) -> AocClient {
+    let client = AocClient::new_with_base(server_url(server).as_str())
+        .expect("creating AoC client")
+        .with_input_cache(dir.to_path_buf());
+    client.set_cookie("deadbeef");
+    client
+}
+
+#[test]
+fn test_get_input_cached() {
+    let server = SERVER_POOL.get_server();
+    let m = request::path(matches("/2018/day/4/input"));
+    server.expect(
+        Expectation::matching(m)
+            .times(1)
+            .respond_with(status_code(200).body("input\n")),
+    );
+    let dir = tempfile::tempdir().unwrap();
+    let client = cached_client(&server, dir.path());
+    assert!(!client.has_cached_input(2018, 4));
+    assert_eq!(client.get_puzzle_input(2018, 4).unwrap(), "input\n");
+    assert!(client.has_cached_input(2018, 4));
+    assert_eq!(client.get_puzzle_input(2018, 4).unwrap(), "input\n");
+    assert_eq!(
+        fs::read_to_string(dir.path().join("2018/day04.txt")).unwrap(),
+        "input\n"
+    );
+}
+
+#[test]
+fn test_get_input_refresh() {
+    let server = SERVER_POOL.get_server();
+    let m = request::path(matches("/2018/day/4/input"));
+    server.expect(Expectation::matching(m).respond_with(status_code(200).body("new\n")));
+    let dir = tempfile::tempdir().unwrap();
+    write_atomically(&dir.path().join("2018/day04.txt"), "old\n").unwrap();
+    let client = cached_client(&server, dir.path());
+    assert_eq!(client.get_puzzle_input(2018, 4).unwrap(), "old\n");
+    let client = client.with_refresh();
+    assert!(!client.has_cached_input(2018, 4));
+    assert_eq!(client.get_puzzle_input(2018, 4).unwrap(), "new\n");
+    assert_eq!(
+        fs::read_to_string(dir.path().join("2018/day04.txt")).unwrap(),
+        "new\n"
+    );
+}
+
+#[test]
+fn test_get_input_web_page_not_cached() {
+    let server = SERVER_POOL.get_server();
+    let m = request::path(matches("/2018/day/4/input"));
+    server.expect(
+        Expectation::matching(m)
+            .respond_with(status_code(200).body("<!DOCTYPE html>\n<html>Log in</html>\n")),
+    );
+    let dir = tempfile::tempdir().unwrap();
+    let client = cached_client(&server, dir.path());
+    assert!(matches!(
+        client.get_puzzle_input(2018, 4),
+        Err(Error::UnexpectedResponse(_))
+    ));
+    assert!(!dir.path().join("2018/day04.txt").exists());
+}
+
+#[test]
+fn test_get_input_without_newline_not_cached() {
+    let server = SERVER_POOL.get_server();
+    let m = request::path(matches("/2018/day/4/input"));
+    server.expect(Expectation::matching(m).respond_with(status_code(200).body("1\n2")));
+    let dir = tempfile::tempdir().unwrap();
+    let client = cached_client(&server, dir.path());
+    assert_eq!(client.get_puzzle_input(2018, 4).unwrap(), "1\n2");
+    assert!(!dir.path().join("2018").exists());
+}
+
+const PUZZLE_PAGE: &str = r#"<html><body><main>
+<article class="day-desc"><h2>--- Day 4: Repose Record ---</h2><p>Part one.</p></article>
+<article class="day-desc"><h2>--- Part Two ---</h2><p>Part two.</p></article>
+</main></body></html>"#;
+
+#[test]
+fn test_get_description_cached_once_complete() {
+    let server = SERVER_POOL.get_server();
+    let m = request::path(matches("/2018/day/4$"));
+    server.expect(
+        Expectation::matching(m)
+            .times(1)
+            .respond_with(status_code(200).body(PUZZLE_PAGE)),
+    );
+    let dir = tempfile::tempdir().unwrap();
+    // A description without part two is downloaded again.
+    write_atomically(&dir.path().join("2018/day04.md"), "## Part one only\n").unwrap();
+    let client = cached_client(&server, dir.path());
+    let expected = "## --- Day 4: Repose Record ---\n\n\
+                    Part one.\n\n\
+                    ## --- Part Two ---\n\n\
+                    Part two.\n";
+    assert_eq!(client.get_puzzle_description(2018, 4).unwrap(), expected);
+    assert_eq!(client.get_puzzle_description(2018, 4).unwrap(), expected);
+    assert_eq!(
+        fs::read_to_string(dir.path().join("2018/day04.md")).unwrap(),
+        expected
+    );
+}
+
+#[test]
+fn test_get_description_offline() {
+    let server = SERVER_POOL.get_server();
+    let m = request::path(matches("/2018/day/4$"));
+    server.expect(
+        Expectation::matching(m)
+            .times(2)
+            .respond_with(status_code(500)),
+    );
+    let dir = tempfile::tempdir().unwrap();
+    let client = cached_client(&server, dir.path());
+    assert!(client.get_puzzle_description(2018, 4).is_err());
+    write_atomically(&dir.path().join("2018/day04.md"), "## Part one only\n").unwrap();
+    assert_eq!(
+        client.get_puzzle_description(2018, 4).unwrap(),
+        "## Part one only\n"
+    );
+}
+
+#[test]
+fn test_get_examples() {
+    let server = SERVER_POOL.get_server();
+    let m = request::path(matches("/2018/day/1$"));
+    let page = r#"<article class="day-desc"><pre><code>+1
+-2
+</code></pre><p>The frequency is <code><em>-1</em></code>.</p></article>"#;
+    server.expect(
+        Expectation::matching(m)
+            .times(2)
+            .respond_with(status_code(200).body(page)),
+    );
+    let dir = tempfile::tempdir().unwrap();
+    let client = cached_client(&server, dir.path());
+    let expected = vec![Example {
+        part: 1,
+        input: "+1\n-2\n".into(),
+        answer: "-1".into(),
+    }];
+    assert_eq!(client.get_puzzle_examples(2018, 1).unwrap(), expected);
+    // Without part two, the examples are downloaded again.
+    assert_eq!(client.get_puzzle_examples(2018, 1).unwrap(), expected);
+    assert!(dir.path().join("2018/day01.examples.json").exists());
+}
+
+const LEADERBOARD: &str = r#"{"event":"2018","owner_id":7,"members":{"7":{"id":7,"name":"Owner",
+"stars":2,"local_score":4,"global_score":0,"last_star_ts":1543650000,"completion_day_level":
+{"1":{"1":{"get_star_ts":1543640000,"star_index":1},"2":{"get_star_ts":1543650000,"star_index":2}}}}}}"#;
+
+#[test]
+fn test_get_leaderboard_cached_for_15_minutes() {
+    let server = SERVER_POOL.get_server();
+    let m = all_of![
+        request::method("GET"),
+        request::path(matches("/2018/leaderboard/private/view/7.json")),
+        request::headers(contains(("cookie", "session=deadbeef"))),
+    ];
+    server.expect(
+        Expectation::matching(m)
+            .times(2)
+            .respond_with(status_code(200).body(LEADERBOARD)),
+    );
+    let dir = tempfile::tempdir().unwrap();
+    let client = cached_client(&server, dir.path()).with_refresh();
+    let leaderboard = client.get_private_leaderboard(2018, 7).unwrap();
+    assert_eq!(leaderboard.members["7"].stars_on(1), 2);
+    assert_eq!(
+        client.get_private_leaderboard(2018, 7).unwrap(),
+        leaderboard
+    );
+
+    let path = dir.path().join("2018/leaderboard-7.json");
+    let stale = std::time::SystemTime::now() - Duration::from_secs(16 * 60);
+    fs::File::options()
+        .write(true)
+        .open(&path)
+        .unwrap()
+        .set_modified(stale)
+        .unwrap();
+    assert_eq!(
+        client.get_private_leaderboard(2018, 7).unwrap(),
+        leaderboard
+    );
+}
+
+#[test]
+fn test_get_leaderboard_stale_when_offline() {
+    let server = SERVER_POOL.get_server();
+    let m = request::path(matches("/2018/leaderboard/private/view/7.json"));
+    server.expect(Expectation::matching(m).respond_with(status_code(502)));
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("2018/leaderboard-7.json");
+    write_atomically(&path, LEADERBOARD).unwrap();
+    let stale = std::time::SystemTime::now() - Duration::from_secs(60 * 60);
+    fs::File::options()
+        .write(true)
+        .open(&path)
+        .unwrap()
+        .set_modified(stale)
+        .unwrap();
+    let client = cached_client(&server, dir.path());
+    assert_eq!(
+        client.get_private_leaderboard(2018, 7).unwrap().event,
+        "2018"
+    );
+}
+
+fn get_input_error(status: u16, body: &'static str) -> Error {
+    let server = SERVER_POOL.get_server();
+    let m = request::path(matches("/2018/day/4/input"));
+    server.expect(Expectation::matching(m).respond_with(status_code(status).body(body)));
+    let client =
+        AocClient::new_with_base(server_url(&server).as_str()).expect("creating AoC client");
+    client.get_puzzle_input(2018, 4).unwrap_err()
+}
+
+#[test]
+fn test_error_not_logged_in() {
+    let e = get_input_error(
+        400,
+        "Puzzle inputs differ by user.  Please log in to get your puzzle input.\n",
+    );
+    assert!(matches!(e, Error::NotLoggedIn));
+    assert!(e.to_string().contains("session cookie"));
+    let e = get_input_error(500, "Please log in.\n");
+    assert!(matches!(e, Error::NotLoggedIn));
+}
+
+#[test]
+fn test_error_puzzle_locked() {
+    let e = get_input_error(404, "404 Not Found\n");
+    assert!(matches!(e, Error::PuzzleLocked { year: 2018, day: 4 }));
+    assert!(
+        e.to_string()
+            .starts_with("day 4 of 2018 is not unlocked yet")
+    );
+}
+
+#[test]
+fn test_error_server() {
+    let e = get_input_error(503, "");
+    assert!(matches!(
+        e,
+        Error::ServerError(StatusCode::SERVICE_UNAVAILABLE)
+    ));
+    assert!(e.to_string().contains("try again"));
+    assert!(matches!(
+        get_input_error(403, ""),
+        Error::UnexpectedResponse(_)
+    ));
+}
+
+#[test]
+fn test_error_transport() {
+    // Nothing listens on a port that was just freed.
+    let port = std::net::TcpListener::bind("127.0.0.1:0")
+        .unwrap()
+        .local_addr()
+        .unwrap()
+        .port();
+    let client = AocClient::new_with_base(&format!("http://127.0.0.1:{port}/"))
+        .expect("creating AoC client");
+    let e = client.get_puzzle_input(2018, 4).unwrap_err();
+    assert!(matches!(e, Error::Transport(_)));
+    assert!(error::Error::source(&e).is_some());
+}
+
+#[test]
+fn test_get_examples_cached_once_part_two_unlocked() {
+    let server = SERVER_POOL.get_server();
+    let m = request::path(matches("/2018/day/1$"));
+    // Part two has no example of its own, and no answer to reuse part one's
+    // example with.
+    let page = r#"<article class="day-desc"><pre><code>+1
+</code></pre><p>The frequency is <code><em>1</em></code>.</p></article>
+<article class="day-desc"><h2>--- Part Two ---</h2><p>Find a repeat.</p></article>"#;
+    server.expect(
+        Expectation::matching(m)
+            .times(1)
+            .respond_with(status_code(200).body(page)),
+    );
+    let dir = tempfile::tempdir().unwrap();
+    let client = cached_client(&server, dir.path());
+    let examples = client.get_puzzle_examples(2018, 1).unwrap();
+    assert_eq!(examples.len(), 1);
+    assert_eq!(client.get_puzzle_examples(2018, 1).unwrap(), examples);
+}
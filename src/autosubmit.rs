@@ -1,22 +1,31 @@
+//! The log of submitted answers.
+
 use crate::aocclient::ValidationResult;
+use crate::solver::Answer;
 
 use std::cmp;
 use std::collections::HashMap;
+use std::convert::Infallible;
 use std::fs;
+use std::path::PathBuf;
 use std::thread::sleep;
 
 use log::debug;
 use serde::Deserialize;
 use serde::Serialize;
 
-const FILE: &str = "results.toml";
+/// Where the submission log is kept unless configured otherwise.
+pub const DEFAULT_FILE: &str = "results.toml";
 
+/// Everything known about the answer to one part of a puzzle.
 #[derive(Debug, Default, Deserialize, Serialize)]
-struct PuzzleLogEntry {
-    rejected_answers: Vec<String>,
-    accepted_answer: Option<String>,
-    upper_bound: Option<i64>,
-    lower_bound: Option<i64>,
+pub struct PuzzleLogEntry {
+    pub rejected_answers: Vec<String>,
+    pub accepted_answer: Option<String>,
+    /// Answers at or above this are known to be too high.
+    pub upper_bound: Option<i64>,
+    /// Answers at or below this are known to be too low.
+    pub lower_bound: Option<i64>,
 }
 
 #[derive(Debug, Default, Deserialize, Serialize)]
@@ -24,134 +33,203 @@ struct Log {
     answers: HashMap<String, PuzzleLogEntry>,
 }
 
-fn puzzle_key(day: i8, level: i8) -> String {
-    format!("day{0}part{1}", day, level)
-}
+/// The only year the submission log was kept for before it became year-aware.
+const LEGACY_YEAR: i16 = 2018;
 
-fn read_submission_log() -> Option<Log> {
-    let log = fs::read_to_string(FILE).ok()?;
-    let log: Log = toml::from_str(log.as_str()).ok()?;
-    Some(log)
+fn puzzle_key(year: i16, day: i8, level: i8) -> String {
+    format!("{year}day{day}part{level}")
 }
 
-fn write_submission_log(log: &Log) {
-    fs::write(FILE, toml::to_string_pretty(log).unwrap().as_str()).unwrap();
+/// The record of all answers submitted so far, kept in a TOML file so that
+/// known-wrong answers are never sent to the server twice.
+pub struct SubmissionLog {
+    path: PathBuf,
 }
 
-fn check_submission_log(day: i8, level: i8, answer: &str) -> Option<ValidationResult> {
-    let log = read_submission_log()?;
-    if let Some(entry) = log.answers.get(puzzle_key(day, level).as_str()) {
-        if let Some(accepted_answer) = &entry.accepted_answer {
-            return Some(if answer == accepted_answer {
-                ValidationResult::Accepted
-            } else {
-                ValidationResult::Rejected
-            });
-        }
-        if let Ok(answer_int) = answer.parse::<i64>() {
-            if let Some(upper_bound) = entry.upper_bound
-                && answer_int >= upper_bound
-            {
-                return Some(ValidationResult::RejectedTooHigh);
+impl SubmissionLog {
+    /// Opens the log kept at `path`. The file is created on the first
+    /// submission.
+    pub fn new<P: Into<PathBuf>>(path: P) -> SubmissionLog {
+        SubmissionLog { path: path.into() }
+    }
+
+    fn read(&self) -> Option<Log> {
+        let log = fs::read_to_string(&self.path).ok()?;
+        let mut log: Log = toml::from_str(log.as_str()).ok()?;
+        // Older logs have keys without a year, which all refer to 2018 puzzles.
+        log.answers = log
+            .answers
+            .into_iter()
+            .map(|(key, entry)| {
+                if key.starts_with("day") {
+                    (format!("{LEGACY_YEAR}{key}"), entry)
+                } else {
+                    (key, entry)
+                }
+            })
+            .collect();
+        Some(log)
+    }
+
+    fn write(&self, log: &Log) {
+        fs::write(&self.path, toml::to_string_pretty(log).unwrap().as_str()).unwrap();
+    }
+
+    fn check(&self, year: i16, day: i8, level: i8, answer: &Answer) -> Option<ValidationResult> {
+        let log = self.read()?;
+        let answer_text = answer.to_string();
+        if let Some(entry) = log.answers.get(puzzle_key(year, day, level).as_str()) {
+            if let Some(accepted_answer) = &entry.accepted_answer {
+                return Some(if answer_text == *accepted_answer {
+                    ValidationResult::Accepted
+                } else {
+                    ValidationResult::Rejected
+                });
             }
-            if let Some(lower_bound) = entry.lower_bound
-                && answer_int <= lower_bound
-            {
-                return Some(ValidationResult::RejectedTooLow);
+            if let Some(answer_int) = answer.as_integer() {
+                if let Some(upper_bound) = entry.upper_bound
+                    && answer_int >= upper_bound
+                {
+                    return Some(ValidationResult::RejectedTooHigh);
+                }
+                if let Some(lower_bound) = entry.lower_bound
+                    && answer_int <= lower_bound
+                {
+                    return Some(ValidationResult::RejectedTooLow);
+                }
+            }
+            if entry.rejected_answers.contains(&answer_text) {
+                return Some(ValidationResult::Rejected);
             }
         }
-        if entry.rejected_answers.iter().any(|a| a == answer) {
-            return Some(ValidationResult::Rejected);
-        }
+        None
     }
-    None
-}
 
-fn record_submission_log(day: i8, level: i8, answer: &str, result: &ValidationResult) {
-    let mut log = read_submission_log()
-        .or_else(|| Some(Log::default()))
-        .unwrap();
-    let key = puzzle_key(day, level);
-    if !log.answers.contains_key(&key) {
-        log.answers.insert(key.clone(), PuzzleLogEntry::default());
-    }
-    let entry = log.answers.get_mut(&key).unwrap();
-    match *result {
-        ValidationResult::Accepted => {
-            entry.accepted_answer = Some(answer.to_string());
+    fn record(&self, year: i16, day: i8, level: i8, answer: &Answer, result: &ValidationResult) {
+        let mut log = self.read().or_else(|| Some(Log::default())).unwrap();
+        let key = puzzle_key(year, day, level);
+        if !log.answers.contains_key(&key) {
+            log.answers.insert(key.clone(), PuzzleLogEntry::default());
         }
-        ValidationResult::Rejected => {
-            if !entry.rejected_answers.iter().any(|a| a == answer) {
-                entry.rejected_answers.push(answer.to_string());
+        let entry = log.answers.get_mut(&key).unwrap();
+        let answer_text = answer.to_string();
+        match *result {
+            ValidationResult::Accepted => {
+                entry.accepted_answer = Some(answer_text);
             }
-        }
-        ValidationResult::RejectedTooLow => {
-            if let Ok(answer_int) = answer.parse::<i64>() {
-                let mut lower_bound = answer_int;
-                if let Some(old_lower_bound) = entry.lower_bound {
-                    lower_bound = cmp::max(lower_bound, old_lower_bound);
+            ValidationResult::Rejected => {
+                if !entry.rejected_answers.contains(&answer_text) {
+                    entry.rejected_answers.push(answer_text);
                 }
-                entry.lower_bound = Some(lower_bound);
             }
-        }
-        ValidationResult::RejectedTooHigh => {
-            if let Ok(answer_int) = answer.parse::<i64>() {
-                let mut upper_bound = answer_int;
-                if let Some(old_upper_bound) = entry.upper_bound {
-                    upper_bound = cmp::min(upper_bound, old_upper_bound);
+            ValidationResult::RejectedTooLow => {
+                if let Some(answer_int) = answer.as_integer() {
+                    let mut lower_bound = answer_int;
+                    if let Some(old_lower_bound) = entry.lower_bound {
+                        lower_bound = cmp::max(lower_bound, old_lower_bound);
+                    }
+                    entry.lower_bound = Some(lower_bound);
                 }
-                entry.upper_bound = Some(upper_bound);
+            }
+            ValidationResult::RejectedTooHigh => {
+                if let Some(answer_int) = answer.as_integer() {
+                    let mut upper_bound = answer_int;
+                    if let Some(old_upper_bound) = entry.upper_bound {
+                        upper_bound = cmp::min(upper_bound, old_upper_bound);
+                    }
+                    entry.upper_bound = Some(upper_bound);
+                }
+            }
+            ValidationResult::Throttled(_) => {
+                panic!("unexpected Throttled value in SubmissionLog::record");
             }
         }
-        ValidationResult::Throttled(_) => {
-            panic!("unexpected Throttled value in record_submission_log");
-        }
+        self.write(&log);
     }
-    write_submission_log(&log);
-}
 
-pub fn submit_with_cache<'a, F>(
-    day: i8,
-    level: i8,
-    answer: &'a str,
-    mut submit_fn: F,
-) -> ValidationResult
-where
-    F: FnMut(i8, i8, &'a str) -> ValidationResult,
-{
-    if let Some(result) = check_submission_log(day, level, answer) {
-        return result;
-    }
-    if answer.is_empty() || answer == "0" {
-        debug!("cowardly refusing to submit the answer of {answer}");
-        return ValidationResult::Rejected;
+    /// Submits `answer` with `submit_fn` unless the log already tells whether it
+    /// is right. Answers that are obviously wrong (zero, empty) or need a human
+    /// to read them (ASCII art) are rejected without asking the server.
+    pub fn submit_with_cache<F>(
+        &self,
+        year: i16,
+        day: i8,
+        level: i8,
+        answer: &Answer,
+        mut submit_fn: F,
+    ) -> ValidationResult
+    where
+        F: FnMut(i16, i8, i8, &str) -> ValidationResult,
+    {
+        let result = self.try_submit_with_cache(year, day, level, answer, |y, d, l, a| {
+            Ok::<_, Infallible>(submit_fn(y, d, l, a))
+        });
+        match result {
+            Ok(result) => result,
+            Err(e) => match e {},
+        }
     }
-    let mut result;
-    loop {
-        result = submit_fn(day, level, answer);
-        if let ValidationResult::Throttled(timeout) = result {
-            sleep(timeout);
-        } else {
-            break;
+
+    /// Like [`SubmissionLog::submit_with_cache`], for a `submit_fn` that can
+    /// fail. Nothing is recorded about a submission that failed.
+    pub fn try_submit_with_cache<F, E>(
+        &self,
+        year: i16,
+        day: i8,
+        level: i8,
+        answer: &Answer,
+        mut submit_fn: F,
+    ) -> Result<ValidationResult, E>
+    where
+        F: FnMut(i16, i8, i8, &str) -> Result<ValidationResult, E>,
+    {
+        if let Some(result) = self.check(year, day, level, answer) {
+            return Ok(result);
         }
+        if !answer.is_submittable() || answer.as_integer() == Some(0) {
+            debug!("cowardly refusing to submit the answer of {answer}");
+            return Ok(ValidationResult::Rejected);
+        }
+        let answer_text = answer.to_string();
+        let mut result;
+        loop {
+            result = submit_fn(year, day, level, &answer_text)?;
+            if let ValidationResult::Throttled(timeout) = result {
+                sleep(timeout);
+            } else {
+                break;
+            }
+        }
+        self.record(year, day, level, answer, &result);
+        Ok(result)
+    }
+
+    /// Returns everything recorded about the submissions for the given puzzle.
+    pub fn puzzle_log_entry(&self, year: i16, day: i8, level: i8) -> Option<PuzzleLogEntry> {
+        self.read()?
+            .answers
+            .remove(puzzle_key(year, day, level).as_str())
     }
-    record_submission_log(day, level, answer, &result);
-    result
-}
 
-pub fn next_unsolved_day() -> i8 {
-    let mut last_fully_solved_day = 0;
-    if let Some(submission_log) = read_submission_log() {
-        for day in 1..24 {
-            if let (Some(part1), Some(part2)) = (
-                submission_log.answers.get(puzzle_key(day, 1).as_str()),
-                submission_log.answers.get(puzzle_key(day, 2).as_str()),
-            ) && part1.accepted_answer.is_some()
-                && part2.accepted_answer.is_some()
-            {
-                last_fully_solved_day = day;
+    /// Returns the day after the last day with both parts accepted.
+    pub fn next_unsolved_day(&self, year: i16) -> i8 {
+        let mut last_fully_solved_day = 0;
+        if let Some(submission_log) = self.read() {
+            for day in 1..24 {
+                if let (Some(part1), Some(part2)) = (
+                    submission_log
+                        .answers
+                        .get(puzzle_key(year, day, 1).as_str()),
+                    submission_log
+                        .answers
+                        .get(puzzle_key(year, day, 2).as_str()),
+                ) && part1.accepted_answer.is_some()
+                    && part2.accepted_answer.is_some()
+                {
+                    last_fully_solved_day = day;
+                }
             }
         }
+        last_fully_solved_day + 1
     }
-    last_fully_solved_day + 1
 }
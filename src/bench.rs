@@ -0,0 +1,241 @@
+use std::panic::{self, AssertUnwindSafe};
+use std::time::Duration;
+
+use crate::runner;
+use crate::timeit;
+use aoc18::context::{self, Context};
+use aoc18::parse::ParseError;
+use aoc18::solver::Solver;
+
+/// Number of untimed runs performed before measuring, so that the first
+/// samples are not skewed by cold caches and allocator setup.
+const WARMUP_ITERATIONS: usize = 2;
+
+/// Summary statistics for a set of timing samples.
+#[derive(Debug, PartialEq)]
+pub struct Statistics {
+    pub min: Duration,
+    pub median: Duration,
+    pub mean: Duration,
+    pub stddev: Duration,
+}
+
+impl Statistics {
+    pub fn from_samples(samples: &[Duration]) -> Statistics {
+        assert!(!samples.is_empty(), "no samples to compute statistics for");
+        let mut sorted = samples.to_vec();
+        sorted.sort();
+        let n = sorted.len();
+        let median = if n.is_multiple_of(2) {
+            (sorted[n / 2 - 1] + sorted[n / 2]) / 2
+        } else {
+            sorted[n / 2]
+        };
+        let mean = sorted.iter().map(Duration::as_secs_f64).sum::<f64>() / n as f64;
+        // Sample standard deviation; a single sample has no spread.
+        let variance = if n > 1 {
+            sorted
+                .iter()
+                .map(|sample| (sample.as_secs_f64() - mean).powi(2))
+                .sum::<f64>()
+                / (n - 1) as f64
+        } else {
+            0.0
+        };
+        Statistics {
+            min: sorted[0],
+            median,
+            mean: Duration::from_secs_f64(mean),
+            stddev: Duration::from_secs_f64(variance.sqrt()),
+        }
+    }
+}
+
+/// The timing statistics of a phase, or why it could not be timed.
+pub type PhaseResult = Result<Statistics, String>;
+
+pub struct BenchmarkReport {
+    pub presolve: PhaseResult,
+    pub part_one: PhaseResult,
+    pub part_two: PhaseResult,
+}
+
+impl BenchmarkReport {
+    /// Whether every phase could be timed.
+    pub fn is_complete(&self) -> bool {
+        self.presolve.is_ok() && self.part_one.is_ok() && self.part_two.is_ok()
+    }
+}
+
+/// The samples of one phase, until it fails.
+#[derive(Default)]
+struct Phase {
+    samples: Vec<Duration>,
+    error: Option<String>,
+}
+
+impl Phase {
+    /// Runs `f` unless the phase has failed already, and keeps its time unless
+    /// this is a warm-up run. Returns `None` if `f` panicked or was cancelled,
+    /// which fails the phase.
+    fn run<T, F: FnOnce() -> T>(&mut self, warm_up: bool, f: F) -> Option<T> {
+        if self.error.is_some() {
+            return None;
+        }
+        match panic::catch_unwind(AssertUnwindSafe(|| timeit(f))) {
+            Ok((result, time)) => {
+                if !warm_up {
+                    self.samples.push(time);
+                }
+                Some(result)
+            }
+            Err(payload) => {
+                self.error = Some(runner::failure_message(payload));
+                None
+            }
+        }
+    }
+
+    fn fail(&mut self, error: &str) {
+        self.error.get_or_insert_with(|| error.into());
+    }
+
+    fn result(self) -> PhaseResult {
+        match self.error {
+            Some(error) => Err(error),
+            None if self.samples.is_empty() => Err("not run".into()),
+            None => Ok(Statistics::from_samples(&self.samples)),
+        }
+    }
+}
+
+/// Runs presolve and both parts `iterations` times, each time on a fresh solver
+/// created by `make_solver`, and reports timing statistics for every phase.
+///
+/// A phase that panics or is interrupted is not run again and is reported as
+/// failed, but the other phases are still timed. Invalid input is an error.
+pub fn benchmark<F>(
+    mut make_solver: F,
+    input: &str,
+    iterations: usize,
+) -> Result<BenchmarkReport, ParseError>
+where
+    F: FnMut() -> Box<dyn Solver>,
+{
+    let mut presolve = Phase::default();
+    let mut part_one = Phase::default();
+    let mut part_two = Phase::default();
+    for iteration in 0..(WARMUP_ITERATIONS + iterations) {
+        if context::is_interrupted() {
+            for phase in [&mut presolve, &mut part_one, &mut part_two] {
+                phase.fail("interrupted");
+            }
+            break;
+        }
+        let warm_up = iteration < WARMUP_ITERATIONS;
+        let mut solver = make_solver();
+        match presolve.run(warm_up, || solver.presolve(input)) {
+            Some(parsed) => parsed?,
+            None => {
+                part_one.fail("presolve failed");
+                part_two.fail("presolve failed");
+                break;
+            }
+        }
+        // A part that fails may leave its solver in any state, so part two
+        // waits for the next run, which skips part one.
+        let part_one_had_failed = part_one.error.is_some();
+        if part_one
+            .run(warm_up, || solver.solve_part_one(&Context::default()))
+            .is_none()
+            && !part_one_had_failed
+        {
+            continue;
+        }
+        part_two.run(warm_up, || solver.solve_part_two(&Context::default()));
+    }
+    Ok(BenchmarkReport {
+        presolve: presolve.result(),
+        part_one: part_one.result(),
+        part_two: part_two.result(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aoc18::context::Cancelled;
+    use aoc18::solver::Answer;
+
+    /// A solver whose parts behave as given.
+    struct TestSolver {
+        part_one: fn() -> Answer,
+        part_two: fn() -> Answer,
+    }
+
+    impl Solver for TestSolver {
+        fn presolve(&mut self, _input: &str) -> Result<(), ParseError> {
+            Ok(())
+        }
+
+        fn solve_part_one(&mut self, _ctx: &Context) -> Answer {
+            (self.part_one)()
+        }
+
+        fn solve_part_two(&mut self, _ctx: &Context) -> Answer {
+            (self.part_two)()
+        }
+    }
+
+    fn bench(part_one: fn() -> Answer, part_two: fn() -> Answer) -> BenchmarkReport {
+        let make_solver = || -> Box<dyn Solver> { Box::new(TestSolver { part_one, part_two }) };
+        benchmark(make_solver, "", 3).unwrap()
+    }
+
+    #[test]
+    fn panicking_part_fails_its_row() {
+        let report = bench(|| panic!("part one is broken"), || Answer::Integer(1));
+        assert!(report.presolve.is_ok());
+        assert_eq!(report.part_one.as_ref().unwrap_err(), "part one is broken");
+        assert!(report.part_two.is_ok());
+    }
+
+    #[test]
+    fn cancelled_part_is_interrupted() {
+        let report = bench(
+            || Answer::Integer(1),
+            || panic::resume_unwind(Box::new(Cancelled)),
+        );
+        assert!(report.part_one.is_ok());
+        assert_eq!(report.part_two.as_ref().unwrap_err(), "interrupted");
+        assert!(!report.is_complete());
+    }
+
+    fn ms(v: u64) -> Duration {
+        Duration::from_millis(v)
+    }
+
+    #[test]
+    fn statistics_odd_count() {
+        let stats = Statistics::from_samples(&[ms(4), ms(1), ms(7)]);
+        assert_eq!(stats.min, ms(1));
+        assert_eq!(stats.median, ms(4));
+        assert_eq!(stats.mean, ms(4));
+        assert_eq!(stats.stddev, ms(3));
+    }
+
+    #[test]
+    fn statistics_even_count() {
+        let stats = Statistics::from_samples(&[ms(2), ms(8), ms(4), ms(6)]);
+        assert_eq!(stats.min, ms(2));
+        assert_eq!(stats.median, ms(5));
+        assert_eq!(stats.mean, ms(5));
+    }
+
+    #[test]
+    fn statistics_single_sample() {
+        let stats = Statistics::from_samples(&[ms(3)]);
+        assert_eq!(stats.median, ms(3));
+        assert_eq!(stats.stddev, Duration::ZERO);
+    }
+}
@@ -0,0 +1,135 @@
+use std::env;
+use std::error;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::Path;
+use std::path::PathBuf;
+
+use serde::Deserialize;
+
+const FILE: &str = "aoc.toml";
+
+/// Optional settings read from `aoc.toml`. Anything given on the command line
+/// or in the environment takes precedence over these.
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+    /// The Advent of Code session cookie.
+    pub session_cookie: Option<String>,
+    /// A file containing the session cookie, used if `session_cookie` is not set.
+    pub cookie_file: Option<PathBuf>,
+    /// Where to keep the log of submitted answers.
+    pub results_log: Option<PathBuf>,
+    /// Where to keep downloaded puzzle inputs.
+    pub input_cache_dir: Option<PathBuf>,
+    /// The Advent of Code event to work on by default.
+    pub year: Option<i16>,
+    /// The base URL of the Advent of Code website.
+    pub base_url: Option<String>,
+}
+
+#[derive(Debug)]
+pub enum Error {
+    IoError(PathBuf, io::Error),
+    ParseError(PathBuf, toml::de::Error),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::IoError(path, e) => write!(f, "cannot read {}: {e}", path.display()),
+            Self::ParseError(path, e) => write!(f, "invalid config file {}: {e}", path.display()),
+        }
+    }
+}
+
+impl error::Error for Error {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match *self {
+            Self::IoError(_, ref e) => Some(e),
+            Self::ParseError(_, ref e) => Some(e),
+        }
+    }
+}
+
+/// Returns the places where the config file is looked for, in order of preference:
+/// the working directory, then the XDG config directory.
+fn candidate_paths() -> Vec<PathBuf> {
+    let mut paths = vec![PathBuf::from(FILE)];
+    let config_home = env::var_os("XDG_CONFIG_HOME")
+        .filter(|dir| !dir.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| env::var_os("HOME").map(|home| Path::new(&home).join(".config")));
+    if let Some(config_home) = config_home {
+        paths.push(config_home.join("aoc18").join(FILE));
+    }
+    paths
+}
+
+impl Config {
+    /// Loads the first config file found, or the default (empty) config if
+    /// there is none.
+    pub fn load() -> Result<Config, Error> {
+        match candidate_paths().into_iter().find(|path| path.is_file()) {
+            Some(path) => Self::load_from(&path),
+            None => Ok(Config::default()),
+        }
+    }
+
+    pub fn load_from(path: &Path) -> Result<Config, Error> {
+        let text = fs::read_to_string(path).map_err(|e| Error::IoError(path.to_path_buf(), e))?;
+        toml::from_str(text.as_str()).map_err(|e| Error::ParseError(path.to_path_buf(), e))
+    }
+
+    /// Returns the session cookie, reading it from `cookie_file` if necessary.
+    pub fn cookie(&self) -> Result<Option<String>, Error> {
+        if let Some(cookie) = &self.session_cookie {
+            return Ok(Some(cookie.clone()));
+        }
+        match &self.cookie_file {
+            Some(path) => fs::read_to_string(path)
+                .map(|cookie| Some(cookie.trim().to_string()))
+                .map_err(|e| Error::IoError(path.clone(), e)),
+            None => Ok(None),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_full_config() {
+        let config: Config = toml::from_str(
+            r#"
+session_cookie = "deadbeef"
+results_log = "/tmp/results.toml"
+input_cache_dir = "/tmp/inputs"
+year = 2019
+base_url = "http://localhost:8080/"
+"#,
+        )
+        .expect("parsing config");
+        assert_eq!(config.cookie().unwrap().as_deref(), Some("deadbeef"));
+        assert_eq!(config.year, Some(2019));
+        assert_eq!(
+            config.results_log.as_deref(),
+            Some(Path::new("/tmp/results.toml"))
+        );
+        assert_eq!(config.base_url.as_deref(), Some("http://localhost:8080/"));
+    }
+
+    #[test]
+    fn parse_empty_config() {
+        let config: Config = toml::from_str("").expect("parsing config");
+        assert_eq!(config.cookie().unwrap(), None);
+        assert_eq!(config.year, None);
+    }
+
+    #[test]
+    fn reject_unknown_keys() {
+        assert!(toml::from_str::<Config>("cookie = \"deadbeef\"").is_err());
+    }
+}
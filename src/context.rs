@@ -0,0 +1,279 @@
+//! What solvers get from whoever runs them: progress reporting, cancellation,
+//! visualization and explanations.
+
+use std::cell::{Cell, RefCell};
+use std::fmt;
+use std::io;
+use std::panic;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, Instant};
+
+use crate::trace::{Trace, TraceEntry};
+use crate::visualize::{FrameSink, Visualize};
+
+/// Set when the user presses Ctrl-C, to cancel every running solver.
+static INTERRUPTED: AtomicBool = AtomicBool::new(false);
+
+/// How often progress messages are passed on, at most.
+const PROGRESS_INTERVAL: Duration = Duration::from_millis(100);
+
+type Reporter = Box<dyn Fn(&str) + Send>;
+
+/// Passes the frames of a visualization on to a sink, up to a limit.
+struct Recorder {
+    sink: Box<dyn FrameSink>,
+    frames: usize,
+    max_frames: usize,
+    error: Option<io::Error>,
+}
+
+/// The payload a cancelled solver unwinds with.
+pub struct Cancelled;
+
+/// Marks every running solver as cancelled. Returns whether this had already
+/// happened.
+pub fn interrupt() -> bool {
+    INTERRUPTED.swap(true, Ordering::Relaxed)
+}
+
+/// Whether the user has asked to stop every running solver.
+pub fn is_interrupted() -> bool {
+    INTERRUPTED.load(Ordering::Relaxed)
+}
+
+/// Passed to the solve methods so that long-running solvers can report
+/// progress and notice when they should stop, and so that solvers can show
+/// their work.
+pub struct Context {
+    cancelled: Arc<AtomicBool>,
+    reporter: Option<Reporter>,
+    last_report: Cell<Option<Instant>>,
+    recorder: Option<RefCell<Recorder>>,
+    trace: Option<RefCell<Trace>>,
+}
+
+impl Default for Context {
+    fn default() -> Context {
+        Context {
+            cancelled: Arc::new(AtomicBool::new(false)),
+            reporter: None,
+            last_report: Cell::new(None),
+            recorder: None,
+            trace: None,
+        }
+    }
+}
+
+impl Context {
+    /// Passes progress messages to `reporter`.
+    pub fn with_reporter<F: Fn(&str) + Send + 'static>(mut self, reporter: F) -> Context {
+        self.reporter = Some(Box::new(reporter));
+        self
+    }
+
+    /// Passes up to `max_frames` frames recorded by the solver to `sink`.
+    pub fn with_recorder(mut self, sink: Box<dyn FrameSink>, max_frames: usize) -> Context {
+        self.recorder = Some(RefCell::new(Recorder {
+            sink,
+            frames: 0,
+            max_frames,
+            error: None,
+        }));
+        self
+    }
+
+    /// Keeps the intermediate results that the solver explains.
+    pub fn with_trace(mut self) -> Context {
+        self.trace = Some(RefCell::new(Trace::default()));
+        self
+    }
+
+    /// Returns a flag that cancels the solver running with this context
+    /// when set.
+    pub fn cancellation(&self) -> Arc<AtomicBool> {
+        self.cancelled.clone()
+    }
+
+    /// Whether the solver should stop. Solvers usually find out from
+    /// [`Context::progress`] instead.
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::Relaxed) || is_interrupted()
+    }
+
+    /// Reports progress, such as the round of a simulation. `message` is only
+    /// called every so often, so this is cheap enough to call in a loop.
+    ///
+    /// This is also a cancellation point: if the solver has been cancelled,
+    /// this unwinds with [`Cancelled`] back to whoever runs the solver.
+    pub fn progress<F: FnOnce() -> String>(&self, message: F) {
+        if self.is_cancelled() {
+            panic::resume_unwind(Box::new(Cancelled));
+        }
+        let Some(reporter) = &self.reporter else {
+            return;
+        };
+        let now = Instant::now();
+        if self
+            .last_report
+            .get()
+            .is_none_or(|last| now - last >= PROGRESS_INTERVAL)
+        {
+            self.last_report.set(Some(now));
+            reporter(&message());
+        }
+    }
+
+    /// Whether recorded frames go anywhere. Solvers only need to check this
+    /// to skip work that is only useful for a visualization.
+    pub fn is_recording(&self) -> bool {
+        self.recorder.as_ref().is_some_and(|recorder| {
+            let recorder = recorder.borrow();
+            recorder.frames < recorder.max_frames && recorder.error.is_none()
+        })
+    }
+
+    /// Records a frame of a visualization. `scene` is only drawn if frames
+    /// are being recorded, so this is cheap enough to call in a loop.
+    pub fn record(&self, scene: &dyn Visualize) {
+        if !self.is_recording() {
+            return;
+        }
+        let mut recorder = self.recorder.as_ref().unwrap().borrow_mut();
+        recorder.frames += 1;
+        if let Err(e) = recorder.sink.write_frame(&scene.frame()) {
+            recorder.error = Some(e);
+        }
+    }
+
+    /// Whether intermediate results are kept. Solvers only need to check this
+    /// to skip work that is only useful for an explanation.
+    pub fn is_explaining(&self) -> bool {
+        self.trace.is_some()
+    }
+
+    /// Records a named intermediate result, such as the guard that sleeps the
+    /// most. `value` is only called in explain mode.
+    pub fn explain<V: fmt::Display, F: FnOnce() -> V>(&self, name: &str, value: F) {
+        if let Some(trace) = &self.trace {
+            let value = value().to_string();
+            trace.borrow_mut().add(name, value);
+        }
+    }
+
+    /// Runs `f`, grouping the results it explains under `name`.
+    pub fn explain_section<T, F: FnOnce() -> T>(&self, name: &str, f: F) -> T {
+        let Some(trace) = &self.trace else {
+            return f();
+        };
+        trace.borrow_mut().open_section(name);
+        let result = f();
+        trace.borrow_mut().close_section();
+        result
+    }
+
+    /// Returns the intermediate results explained so far.
+    pub fn take_trace(&self) -> Vec<TraceEntry> {
+        self.trace
+            .as_ref()
+            .map_or(vec![], |trace| trace.take().into_entries())
+    }
+
+    /// Finishes the recording, if any, and returns how many frames were
+    /// recorded.
+    pub fn finish_recording(self) -> io::Result<usize> {
+        let Some(recorder) = self.recorder else {
+            return Ok(0);
+        };
+        let recorder = recorder.into_inner();
+        if let Some(e) = recorder.error {
+            return Err(e);
+        }
+        recorder.sink.finish()?;
+        Ok(recorder.frames)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Mutex;
+
+    use crate::visualize::Frame;
+
+    struct Sink(Arc<Mutex<Vec<Frame>>>);
+
+    impl FrameSink for Sink {
+        fn write_frame(&mut self, frame: &Frame) -> io::Result<()> {
+            self.0.lock().unwrap().push(frame.clone());
+            Ok(())
+        }
+
+        fn finish(self: Box<Self>) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    struct Step(i64);
+
+    impl Visualize for Step {
+        fn frame(&self) -> Frame {
+            Frame::new(self.0, 0, 1, 1)
+        }
+    }
+
+    #[test]
+    fn progress_is_throttled() {
+        let messages = Arc::new(Mutex::new(vec![]));
+        let ctx = Context::default().with_reporter({
+            let messages = messages.clone();
+            move |message| messages.lock().unwrap().push(message.to_string())
+        });
+        for i in 0..1000 {
+            ctx.progress(|| format!("step {i}"));
+        }
+        assert_eq!(*messages.lock().unwrap(), vec!["step 0".to_string()]);
+    }
+
+    #[test]
+    fn cancelled_solver_unwinds() {
+        let ctx = Context::default();
+        ctx.cancellation().store(true, Ordering::Relaxed);
+        let result = panic::catch_unwind(panic::AssertUnwindSafe(|| ctx.progress(String::new)));
+        assert!(result.unwrap_err().is::<Cancelled>());
+    }
+
+    #[test]
+    fn recording_stops_at_max_frames() {
+        let frames = Arc::new(Mutex::new(vec![]));
+        let ctx = Context::default().with_recorder(Box::new(Sink(frames.clone())), 3);
+        assert!(ctx.is_recording());
+        for i in 0..10 {
+            ctx.record(&Step(i));
+        }
+        assert!(!ctx.is_recording());
+        assert_eq!(ctx.finish_recording().unwrap(), 3);
+        assert_eq!(
+            *frames.lock().unwrap(),
+            (0..3).map(|i| Step(i).frame()).collect::<Vec<_>>()
+        );
+        assert_eq!(Context::default().finish_recording().unwrap(), 0);
+    }
+
+    #[test]
+    fn explain_only_in_explain_mode() {
+        let ctx = Context::default();
+        ctx.explain("never", || -> i32 { panic!("value computed") });
+        assert!(ctx.take_trace().is_empty());
+
+        let ctx = Context::default().with_trace();
+        let answer = ctx.explain_section("section", || {
+            ctx.explain("value", || 42);
+            42
+        });
+        assert_eq!(answer, 42);
+        let trace = ctx.take_trace();
+        assert_eq!(trace.len(), 1);
+        assert_eq!(trace[0].children[0].value.as_deref(), Some("42"));
+    }
+}
@@ -1,5 +1,13 @@
 use std::collections::HashSet;
 
+use crate::context::Context;
+use crate::parse::ParseError;
+use crate::parse::input_lines;
+use crate::parse::parse_at;
+use crate::registry::REFERENCE;
+use crate::registry::SolverInfo;
+use crate::registry::Tag;
+use crate::solver::Answer;
 use crate::solver::Solver;
 
 #[derive(Default)]
@@ -8,26 +16,28 @@ pub struct Day1Solver {
 }
 
 impl Solver for Day1Solver {
-    fn presolve(&mut self, input: &str) {
-        self.input = input
-            .trim()
-            .split("\n")
-            .map(|line| line.parse::<isize>().unwrap())
-            .collect();
+    fn presolve(&mut self, input: &str) -> Result<(), ParseError> {
+        const EXPECTED: &str = "a frequency change such as +7 or -3";
+        self.input = input_lines(input, EXPECTED)?
+            .into_iter()
+            .map(|(line_no, line)| parse_at(line_no, 1, line, EXPECTED))
+            .collect::<Result<_, _>>()?;
+        Ok(())
     }
 
-    fn solve_part_one(&mut self) -> String {
-        self.input.iter().sum::<isize>().to_string()
+    fn solve_part_one(&mut self, _ctx: &Context) -> Answer {
+        self.input.iter().sum::<isize>().into()
     }
 
-    fn solve_part_two(&mut self) -> String {
+    fn solve_part_two(&mut self, ctx: &Context) -> Answer {
         let mut set = HashSet::<isize>::new();
         let mut frequency = 0;
         loop {
+            ctx.progress(|| format!("{} frequencies seen", set.len()));
             for v in self.input.iter() {
                 frequency += v;
                 if set.contains(&frequency) {
-                    return frequency.to_string();
+                    return frequency.into();
                 }
                 set.insert(frequency);
             }
@@ -39,11 +49,24 @@ pub fn solver() -> Day1Solver {
     Day1Solver::default()
 }
 
+inventory::submit! {
+    SolverInfo {
+        year: 2018,
+        day: 1,
+        implementation: REFERENCE,
+        title: "Chronal Calibration",
+        tags: &[Tag::Math],
+        make_solver: || Box::new(solver()),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use test_log::test;
 
+    use crate::testing::assert_cancellable;
+
     #[test]
     fn provided_example() {
         let example = "+1
@@ -51,8 +74,8 @@ mod tests {
 +1
 ";
         let mut s = solver();
-        s.presolve(example);
-        assert_eq!("3", s.solve_part_one());
+        s.presolve(example).unwrap();
+        assert_eq!(Answer::Integer(3), s.solve_part_one(&Context::default()));
     }
 
     #[test]
@@ -64,7 +87,14 @@ mod tests {
 -4
 ";
         let mut s = solver();
-        s.presolve(example);
-        assert_eq!("14", s.solve_part_two());
+        s.presolve(example).unwrap();
+        assert_eq!(Answer::Integer(14), s.solve_part_two(&Context::default()));
+    }
+
+    #[test]
+    fn frequency_that_never_repeats_is_cancelled() {
+        let mut s = solver();
+        s.presolve("+1\n").unwrap();
+        assert_cancellable(|ctx| s.solve_part_two(ctx));
     }
 }
@@ -1,5 +1,12 @@
 use std::collections::HashMap;
 
+use crate::context::Context;
+use crate::parse::ParseError;
+use crate::parse::input_lines;
+use crate::registry::REFERENCE;
+use crate::registry::SolverInfo;
+use crate::registry::Tag;
+use crate::solver::Answer;
 use crate::solver::Solver;
 
 #[derive(Default)]
@@ -8,15 +15,26 @@ pub struct Day1Solver {
 }
 
 impl Solver for Day1Solver {
-    fn presolve(&mut self, input: &str) {
-        self.input = input
-            .trim()
-            .split("\n")
-            .map(|line| line.to_string())
-            .collect();
+    fn presolve(&mut self, input: &str) -> Result<(), ParseError> {
+        const EXPECTED: &str = "a box ID of lowercase letters";
+        self.input = input_lines(input, EXPECTED)?
+            .into_iter()
+            .map(|(line_no, line)| {
+                match line.char_indices().find(|(_, ch)| !ch.is_ascii_lowercase()) {
+                    Some((idx, ch)) => Err(ParseError::new(
+                        line_no,
+                        idx + 1,
+                        ch.to_string().as_str(),
+                        EXPECTED,
+                    )),
+                    None => Ok(line.to_string()),
+                }
+            })
+            .collect::<Result<_, _>>()?;
+        Ok(())
     }
 
-    fn solve_part_one(&mut self) -> String {
+    fn solve_part_one(&mut self, _ctx: &Context) -> Answer {
         let mut with_two = 0;
         let mut with_three = 0;
         for box_id in self.input.iter() {
@@ -32,10 +50,10 @@ impl Solver for Day1Solver {
                 with_three += 1;
             }
         }
-        (with_three * with_two).to_string()
+        (with_three * with_two).into()
     }
 
-    fn solve_part_two(&mut self) -> String {
+    fn solve_part_two(&mut self, _ctx: &Context) -> Answer {
         for a in self.input.iter() {
             for b in self.input.iter() {
                 let common_chars = String::from_iter(
@@ -45,7 +63,7 @@ impl Solver for Day1Solver {
                         .map(|(a, _)| a),
                 );
                 if common_chars.len() == b.len() - 1 {
-                    return common_chars;
+                    return Answer::Text(common_chars);
                 }
             }
         }
@@ -57,6 +75,17 @@ pub fn solver() -> Day1Solver {
     Day1Solver::default()
 }
 
+inventory::submit! {
+    SolverInfo {
+        year: 2018,
+        day: 2,
+        implementation: REFERENCE,
+        title: "Inventory Management System",
+        tags: &[Tag::Strings],
+        make_solver: || Box::new(solver()),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -73,8 +102,8 @@ abcdee
 ababab
 ";
         let mut s = solver();
-        s.presolve(example);
-        assert_eq!("12", s.solve_part_one());
+        s.presolve(example).unwrap();
+        assert_eq!(Answer::Integer(12), s.solve_part_one(&Context::default()));
     }
 
     #[test]
@@ -88,7 +117,10 @@ axcye
 wvxyz
 ";
         let mut s = solver();
-        s.presolve(example);
-        assert_eq!("fgij", s.solve_part_two());
+        s.presolve(example).unwrap();
+        assert_eq!(
+            Answer::Text("fgij".into()),
+            s.solve_part_two(&Context::default())
+        );
     }
 }
@@ -2,6 +2,15 @@ use std::collections::{HashMap, HashSet};
 
 use regex::Regex;
 
+use crate::context::Context;
+use crate::parse::ParseError;
+use crate::parse::captures;
+use crate::parse::input_lines;
+use crate::parse::parse_group;
+use crate::registry::REFERENCE;
+use crate::registry::SolverInfo;
+use crate::registry::Tag;
+use crate::solver::Answer;
 use crate::solver::Solver;
 
 #[derive(Default)]
@@ -10,24 +19,25 @@ pub struct PuzzleSolver {
 }
 
 impl Solver for PuzzleSolver {
-    fn presolve(&mut self, input: &str) {
+    fn presolve(&mut self, input: &str) -> Result<(), ParseError> {
+        const EXPECTED: &str = "a claim such as #1 @ 1,3: 4x4";
         let re = Regex::new(r"#\d+ @ (\d+),(\d+): (\d+)x(\d+)").unwrap();
-        self.input = input
-            .trim()
-            .split("\n")
-            .map(|line| re.captures(line).unwrap().extract())
-            .map(|(_, [x, y, w, h])| {
-                (
-                    x.parse().unwrap(),
-                    y.parse().unwrap(),
-                    w.parse().unwrap(),
-                    h.parse().unwrap(),
-                )
+        self.input = input_lines(input, EXPECTED)?
+            .into_iter()
+            .map(|(line_no, line)| {
+                let c = captures(&re, line_no, line, EXPECTED)?;
+                Ok((
+                    parse_group(&c, 1, line_no, "a left offset")?,
+                    parse_group(&c, 2, line_no, "a top offset")?,
+                    parse_group(&c, 3, line_no, "a width")?,
+                    parse_group(&c, 4, line_no, "a height")?,
+                ))
             })
-            .collect();
+            .collect::<Result<_, _>>()?;
+        Ok(())
     }
 
-    fn solve_part_one(&mut self) -> String {
+    fn solve_part_one(&mut self, _ctx: &Context) -> Answer {
         let mut claim_count_per_inch = HashMap::<(i64, i64), i64>::new();
         for &(start_x, start_y, w, h) in self.input.iter() {
             for x in start_x..(start_x + w) {
@@ -40,10 +50,10 @@ impl Solver for PuzzleSolver {
             .into_values()
             .filter(|&v| v > 1)
             .count()
-            .to_string()
+            .into()
     }
 
-    fn solve_part_two(&mut self) -> String {
+    fn solve_part_two(&mut self, _ctx: &Context) -> Answer {
         let mut claims_per_inch = HashMap::<(i64, i64), HashSet<usize>>::new();
         let mut candidate_claims = HashSet::<usize>::new();
         for (claim_idx, &(start_x, start_y, w, h)) in self.input.iter().enumerate() {
@@ -61,7 +71,7 @@ impl Solver for PuzzleSolver {
             .for_each(|claim_idx| {
                 candidate_claims.remove(&claim_idx);
             });
-        (candidate_claims.iter().next().unwrap() + 1).to_string()
+        (candidate_claims.iter().next().unwrap() + 1).into()
     }
 }
 
@@ -69,6 +79,17 @@ pub fn solver() -> PuzzleSolver {
     PuzzleSolver::default()
 }
 
+inventory::submit! {
+    SolverInfo {
+        year: 2018,
+        day: 3,
+        implementation: REFERENCE,
+        title: "No Matter How You Slice It",
+        tags: &[Tag::Grid],
+        make_solver: || Box::new(solver()),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -82,8 +103,9 @@ mod tests {
 #2 @ 3,1: 4x4
 #3 @ 5,5: 2x2
 ",
-        );
-        assert_eq!("4", s.solve_part_one());
+        )
+        .unwrap();
+        assert_eq!(Answer::Integer(4), s.solve_part_one(&Context::default()));
     }
 
     #[test]
@@ -94,7 +116,8 @@ mod tests {
 #2 @ 3,1: 4x4
 #3 @ 5,5: 2x2
 ",
-        );
-        assert_eq!("3", s.solve_part_two());
+        )
+        .unwrap();
+        assert_eq!(Answer::Integer(3), s.solve_part_two(&Context::default()));
     }
 }
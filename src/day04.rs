@@ -2,6 +2,15 @@ use std::collections::{HashMap, HashSet};
 
 use regex::Regex;
 
+use crate::context::Context;
+use crate::parse::ParseError;
+use crate::parse::captures;
+use crate::parse::input_lines;
+use crate::parse::parse_group;
+use crate::registry::REFERENCE;
+use crate::registry::SolverInfo;
+use crate::registry::Tag;
+use crate::solver::Answer;
 use crate::solver::Solver;
 
 enum Event {
@@ -16,40 +25,36 @@ pub struct PuzzleSolver {
 }
 
 impl Solver for PuzzleSolver {
-    fn presolve(&mut self, input: &str) {
+    fn presolve(&mut self, input: &str) -> Result<(), ParseError> {
+        const EXPECTED: &str = "a record such as [1518-11-01 00:05] falls asleep";
         let re = Regex::new(
             r"\[([^ ]+) (\d\d):(\d\d)\] (wakes up|falls asleep|Guard #(\d+) begins shift)",
         )
         .unwrap();
-        self.input = input
-            .trim()
-            .split("\n")
-            .map(|line| match re.captures(line) {
-                None => {
-                    panic!("unable to parse line: >{line}<");
-                }
-                Some(c) => c,
-            })
-            .map(|m| {
+        self.input = input_lines(input, EXPECTED)?
+            .into_iter()
+            .map(|(line_no, line)| {
+                let m = captures(&re, line_no, line, EXPECTED)?;
                 let date = m.get(1).unwrap().as_str().to_string();
                 let hour = m.get(2).unwrap().as_str().to_string();
                 let minute = m.get(3).unwrap().as_str().to_string();
                 let event = match m.get(4).unwrap().as_str() {
                     "wakes up" => Event::WakesUp,
                     "falls asleep" => Event::FallsAsleep,
-                    _ => Event::BeginsShift(m.get(5).unwrap().as_str().parse().unwrap()),
+                    _ => Event::BeginsShift(parse_group(&m, 5, line_no, "a guard number")?),
                 };
-                (date, hour, minute, event)
+                Ok((date, hour, minute, event))
             })
-            .collect();
+            .collect::<Result<_, _>>()?;
         self.input.sort_by(
             |(a_date, a_hour, a_minute, _), (b_date, b_hour, b_minute, _)| {
                 (a_date, a_hour, a_minute).cmp(&(b_date, b_hour, b_minute))
             },
         );
+        Ok(())
     }
 
-    fn solve_part_one(&mut self) -> String {
+    fn solve_part_one(&mut self, ctx: &Context) -> Answer {
         let mut current_guard = -1;
         let mut current_sleep_started_at = -1;
         let mut spent_sleeping_per_guard_per_minute = HashMap::<(i64, i64), i64>::new();
@@ -72,27 +77,35 @@ impl Solver for PuzzleSolver {
                 }
             }
         }
+        let minutes_asleep = |guard_id: i64| {
+            spent_sleeping_per_guard_per_minute
+                .iter()
+                .filter(|((g, _), _)| *g == guard_id)
+                .map(|(_, v)| *v)
+                .sum::<i64>()
+        };
         let sleepiest_guard = *guard_ids
             .iter()
-            .max_by_key(|&guard_id| {
-                spent_sleeping_per_guard_per_minute
-                    .iter()
-                    .filter(|((g, _), _)| *g == *guard_id)
-                    .map(|(_, v)| *v)
-                    .sum::<i64>()
-            })
+            .max_by_key(|&guard_id| minutes_asleep(*guard_id))
             .unwrap();
-        let sleepiest_minute = spent_sleeping_per_guard_per_minute
+        ctx.explain("sleepiest guard", || {
+            format!(
+                "#{sleepiest_guard}, asleep for {} minutes",
+                minutes_asleep(sleepiest_guard)
+            )
+        });
+        let (&(_, sleepiest_minute), days_on_which_slept) = spent_sleeping_per_guard_per_minute
             .iter()
             .filter(|((g, _), _)| *g == sleepiest_guard)
             .max_by_key(|(_, days_on_which_slept)| **days_on_which_slept)
-            .unwrap()
-            .0
-            .1;
-        (sleepiest_guard * sleepiest_minute).to_string()
+            .unwrap();
+        ctx.explain("sleepiest minute", || {
+            format!("00:{sleepiest_minute:02}, asleep on {days_on_which_slept} days")
+        });
+        (sleepiest_guard * sleepiest_minute).into()
     }
 
-    fn solve_part_two(&mut self) -> String {
+    fn solve_part_two(&mut self, ctx: &Context) -> Answer {
         let mut current_guard = -1;
         let mut current_sleep_started_at = -1;
         let mut spent_sleeping_per_guard_per_minute = HashMap::<(i64, i64), i64>::new();
@@ -115,12 +128,17 @@ impl Solver for PuzzleSolver {
                 }
             }
         }
-        let (sleepiest_guard, sleepiest_minute) = spent_sleeping_per_guard_per_minute
-            .iter()
-            .max_by_key(|(_, days_on_which_slept)| **days_on_which_slept)
-            .unwrap()
-            .0;
-        (*sleepiest_guard * *sleepiest_minute).to_string()
+        let ((sleepiest_guard, sleepiest_minute), days_on_which_slept) =
+            spent_sleeping_per_guard_per_minute
+                .iter()
+                .max_by_key(|(_, days_on_which_slept)| **days_on_which_slept)
+                .unwrap();
+        ctx.explain("most frequently asleep", || {
+            format!(
+                "guard #{sleepiest_guard} at 00:{sleepiest_minute:02}, on {days_on_which_slept} days"
+            )
+        });
+        (*sleepiest_guard * *sleepiest_minute).into()
     }
 }
 
@@ -128,9 +146,21 @@ pub fn solver() -> PuzzleSolver {
     PuzzleSolver::default()
 }
 
+inventory::submit! {
+    SolverInfo {
+        year: 2018,
+        day: 4,
+        implementation: REFERENCE,
+        title: "Repose Record",
+        tags: &[Tag::Parsing],
+        make_solver: || Box::new(solver()),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::trace::render;
     use test_log::test;
 
     #[test]
@@ -155,8 +185,9 @@ mod tests {
 [1518-11-05 00:45] falls asleep
 [1518-11-05 00:55] wakes up
 ",
-        );
-        assert_eq!("240", s.solve_part_one());
+        )
+        .unwrap();
+        assert_eq!(Answer::Integer(240), s.solve_part_one(&Context::default()));
     }
 
     #[test]
@@ -181,7 +212,58 @@ mod tests {
 [1518-11-05 00:45] falls asleep
 [1518-11-05 00:55] wakes up
 ",
+        )
+        .unwrap();
+        assert_eq!(Answer::Integer(4455), s.solve_part_two(&Context::default()));
+    }
+
+    #[test]
+    fn explain_trace() {
+        let mut s = solver();
+        s.presolve(
+            "[1518-11-01 00:00] Guard #10 begins shift
+[1518-11-01 00:05] falls asleep
+[1518-11-01 00:25] wakes up
+[1518-11-01 00:30] falls asleep
+[1518-11-01 00:55] wakes up
+[1518-11-01 23:58] Guard #99 begins shift
+[1518-11-02 00:40] falls asleep
+[1518-11-02 00:50] wakes up
+[1518-11-03 00:05] Guard #10 begins shift
+[1518-11-03 00:24] falls asleep
+[1518-11-03 00:29] wakes up
+[1518-11-04 00:02] Guard #99 begins shift
+[1518-11-04 00:36] falls asleep
+[1518-11-04 00:46] wakes up
+[1518-11-05 00:03] Guard #99 begins shift
+[1518-11-05 00:45] falls asleep
+[1518-11-05 00:55] wakes up
+",
+        )
+        .unwrap();
+        let ctx = Context::default().with_trace();
+        s.solve_part_one(&ctx);
+        assert_eq!(
+            render(&ctx.take_trace()),
+            "sleepiest guard: #10, asleep for 50 minutes\n\
+             sleepiest minute: 00:24, asleep on 2 days\n"
+        );
+    }
+
+    #[test]
+    fn invalid_guard_number() {
+        let mut s = solver();
+        assert_eq!(
+            s.presolve(
+                "[1518-11-01 00:00] Guard #10 begins shift
+[1518-11-01 00:05] Guard #99999999999999999999 begins shift"
+            ),
+            Err(ParseError::new(
+                2,
+                27,
+                "99999999999999999999",
+                "a guard number"
+            ))
         );
-        assert_eq!("4455", s.solve_part_two());
     }
 }
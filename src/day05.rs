@@ -1,3 +1,10 @@
+use crate::context::Context;
+use crate::parse::ParseError;
+use crate::parse::input_line;
+use crate::registry::REFERENCE;
+use crate::registry::SolverInfo;
+use crate::registry::Tag;
+use crate::solver::Answer;
 use crate::solver::Solver;
 
 #[derive(Default)]
@@ -5,12 +12,30 @@ pub struct PuzzleSolver {
     input: String,
 }
 
+fn parse_polymer(input: &str) -> Result<String, ParseError> {
+    const EXPECTED: &str = "a polymer of letters";
+    let (line_no, line) = input_line(input, EXPECTED)?;
+    if let Some((idx, ch)) = line
+        .char_indices()
+        .find(|(_, ch)| !ch.is_ascii_alphabetic())
+    {
+        return Err(ParseError::new(
+            line_no,
+            idx + 1,
+            ch.to_string().as_str(),
+            EXPECTED,
+        ));
+    }
+    Ok(line.to_string())
+}
+
 impl Solver for PuzzleSolver {
-    fn presolve(&mut self, input: &str) {
-        self.input = input.trim().to_string();
+    fn presolve(&mut self, input: &str) -> Result<(), ParseError> {
+        self.input = parse_polymer(input)?;
+        Ok(())
     }
 
-    fn solve_part_one(&mut self) -> String {
+    fn solve_part_one(&mut self, _ctx: &Context) -> Answer {
         let mut string: Vec<_> = self.input.chars().collect();
         loop {
             let mut modified = false;
@@ -29,10 +54,10 @@ impl Solver for PuzzleSolver {
                 break;
             }
         }
-        string.len().to_string()
+        string.len().into()
     }
 
-    fn solve_part_two(&mut self) -> String {
+    fn solve_part_two(&mut self, _ctx: &Context) -> Answer {
         let mut min = usize::MAX;
         for remove in 'a'..='z' {
             let mut string: Vec<_> = self
@@ -60,7 +85,7 @@ impl Solver for PuzzleSolver {
             }
             min = std::cmp::min(min, string.len());
         }
-        min.to_string()
+        min.into()
     }
 }
 
@@ -68,6 +93,78 @@ pub fn solver() -> PuzzleSolver {
     PuzzleSolver::default()
 }
 
+/// Reacts the polymer in a single pass: every unit either reacts with the
+/// last unit that is left so far, or is left itself.
+#[derive(Default)]
+pub struct StackSolver {
+    input: Vec<u8>,
+}
+
+fn react<I: Iterator<Item = u8>>(units: I) -> Vec<u8> {
+    let mut stack: Vec<u8> = vec![];
+    for unit in units {
+        match stack.last() {
+            Some(&last) if last != unit && last.eq_ignore_ascii_case(&unit) => {
+                stack.pop();
+            }
+            _ => stack.push(unit),
+        }
+    }
+    stack
+}
+
+impl Solver for StackSolver {
+    fn presolve(&mut self, input: &str) -> Result<(), ParseError> {
+        self.input = parse_polymer(input)?.into_bytes();
+        Ok(())
+    }
+
+    fn solve_part_one(&mut self, _ctx: &Context) -> Answer {
+        react(self.input.iter().copied()).len().into()
+    }
+
+    fn solve_part_two(&mut self, _ctx: &Context) -> Answer {
+        // Removing a unit type and reacting gives the same result whether or
+        // not the rest has already reacted, which makes for a shorter polymer.
+        let reacted = react(self.input.iter().copied());
+        (b'a'..=b'z')
+            .map(|remove| {
+                react(
+                    reacted
+                        .iter()
+                        .copied()
+                        .filter(|unit| unit.to_ascii_lowercase() != remove),
+                )
+                .len()
+            })
+            .min()
+            .unwrap()
+            .into()
+    }
+}
+
+inventory::submit! {
+    SolverInfo {
+        year: 2018,
+        day: 5,
+        implementation: REFERENCE,
+        title: "Alchemical Reduction",
+        tags: &[Tag::Strings],
+        make_solver: || Box::new(solver()),
+    }
+}
+
+inventory::submit! {
+    SolverInfo {
+        year: 2018,
+        day: 5,
+        implementation: "stack",
+        title: "Alchemical Reduction",
+        tags: &[Tag::Strings],
+        make_solver: || Box::new(StackSolver::default()),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -76,14 +173,22 @@ mod tests {
     #[test]
     fn provided_example() {
         let mut s = solver();
-        s.presolve("dabAcCaCBAcCcaDA");
-        assert_eq!("10", s.solve_part_one());
+        s.presolve("dabAcCaCBAcCcaDA").unwrap();
+        assert_eq!(Answer::Integer(10), s.solve_part_one(&Context::default()));
     }
 
     #[test]
     fn provided_example_with_nl() {
         let mut s = solver();
-        s.presolve("dabAcCaCBAcCcaDA\n");
-        assert_eq!("4", s.solve_part_two());
+        s.presolve("dabAcCaCBAcCcaDA\n").unwrap();
+        assert_eq!(Answer::Integer(4), s.solve_part_two(&Context::default()));
+    }
+
+    #[test]
+    fn stack_solver_agrees() {
+        let mut s = StackSolver::default();
+        s.presolve("dabAcCaCBAcCcaDA\n").unwrap();
+        assert_eq!(Answer::Integer(10), s.solve_part_one(&Context::default()));
+        assert_eq!(Answer::Integer(4), s.solve_part_two(&Context::default()));
     }
 }
@@ -1,28 +1,52 @@
 use std::{collections::HashSet, iter::repeat_n};
 
 use itertools::Itertools;
-use log::debug;
 
+use crate::context::Context;
+use crate::parse::ParseError;
+use crate::parse::input_lines;
+use crate::parse::parse_at;
+use crate::registry::REFERENCE;
+use crate::registry::SolverInfo;
+use crate::registry::Tag;
+use crate::solver::Answer;
+use crate::solver::Param;
 use crate::solver::Solver;
+use crate::solver::with_default_params;
+
+const PARAMS: &[Param] = &[Param {
+    name: "max_total_distance",
+    description: "Total distance to all coordinates below which a location is in the region",
+    default: 10000,
+    min: 1,
+    max: i64::MAX,
+}];
 
 #[derive(Default)]
 pub struct PuzzleSolver {
     input: Vec<(i64, i64)>,
+    max_total_distance: i64,
 }
 
 impl Solver for PuzzleSolver {
-    fn presolve(&mut self, input: &str) {
-        self.input = input
-            .trim()
-            .lines()
-            .map(|line| {
-                let (x, y) = line.split_once(", ").unwrap();
-                (x.parse().unwrap(), y.parse().unwrap())
+    fn presolve(&mut self, input: &str) -> Result<(), ParseError> {
+        const EXPECTED: &str = "coordinates such as 1, 6";
+        self.input = input_lines(input, EXPECTED)?
+            .into_iter()
+            .map(|(line_no, line)| {
+                let Some((x, y)) = line.split_once(", ") else {
+                    return Err(ParseError::new(line_no, 1, line, EXPECTED));
+                };
+                Ok((
+                    parse_at(line_no, 1, x, "an X coordinate")?,
+                    parse_at(line_no, x.len() + 3, y, "a Y coordinate")?,
+                ))
             })
-            .collect();
+            .collect::<Result<_, _>>()?;
+        Ok(())
     }
 
-    fn solve_part_one(&mut self) -> String {
+    fn solve_part_one(&mut self, ctx: &Context) -> Answer {
         let (mut left, mut right, mut top, mut bottom) = (
             self.input[0].0,
             self.input[0].0,
@@ -85,7 +109,19 @@ impl Solver for PuzzleSolver {
                 }
             }
         }
-        debug!("{:?}", areas_by_idx);
+        if ctx.is_explaining() {
+            ctx.explain_section("area around each coordinate", || {
+                for (idx, ((x, y), area)) in self.input.iter().zip(&areas_by_idx).enumerate() {
+                    ctx.explain(&format!("{x}, {y}"), || {
+                        if infinites.contains(&idx) {
+                            "infinite".to_string()
+                        } else {
+                            area.to_string()
+                        }
+                    });
+                }
+            });
+        }
         areas_by_idx
             .iter()
             .enumerate()
@@ -93,10 +129,10 @@ impl Solver for PuzzleSolver {
             .map(|(_, area)| *area)
             .max()
             .unwrap()
-            .to_string()
+            .into()
     }
 
-    fn solve_part_two(&mut self) -> String {
+    fn solve_part_two(&mut self, _ctx: &Context) -> Answer {
         let (mut left, mut right, mut top, mut bottom) = (
             self.input[0].0,
             self.input[0].0,
@@ -117,7 +153,7 @@ impl Solver for PuzzleSolver {
                     .iter()
                     .map(|(coord_x, coord_y)| (x - *coord_x).abs() + (y - *coord_y).abs())
                     .sum::<i64>()
-                    < 10000
+                    < self.max_total_distance
                 {
                     region_size += 1;
                 }
@@ -133,7 +169,7 @@ impl Solver for PuzzleSolver {
                         .iter()
                         .map(|(coord_x, coord_y)| (x - *coord_x).abs() + (y - *coord_y).abs())
                         .sum::<i64>()
-                        < 10000
+                        < self.max_total_distance
                     {
                         additional_region += 1;
                     }
@@ -146,7 +182,7 @@ impl Solver for PuzzleSolver {
                         .iter()
                         .map(|(coord_x, coord_y)| (x - *coord_x).abs() + (y - *coord_y).abs())
                         .sum::<i64>()
-                        < 10000
+                        < self.max_total_distance
                     {
                         additional_region += 1;
                     }
@@ -158,12 +194,34 @@ impl Solver for PuzzleSolver {
             region_size += additional_region;
             expand_box_by += 1;
         }
-        region_size.to_string()
+        region_size.into()
+    }
+
+    fn params(&self) -> &'static [Param] {
+        PARAMS
+    }
+
+    fn set_param(&mut self, name: &str, value: i64) {
+        match name {
+            "max_total_distance" => self.max_total_distance = value,
+            _ => unreachable!("unknown parameter {name}"),
+        }
     }
 }
 
 pub fn solver() -> PuzzleSolver {
-    PuzzleSolver::default()
+    with_default_params(PuzzleSolver::default())
+}
+
+inventory::submit! {
+    SolverInfo {
+        year: 2018,
+        day: 6,
+        implementation: REFERENCE,
+        title: "Chronal Coordinates",
+        tags: &[Tag::Grid],
+        make_solver: || Box::new(solver()),
+    }
 }
 
 #[cfg(test)]
@@ -182,8 +240,17 @@ mod tests {
 5, 5
 8, 9
 ",
-        );
-        assert_eq!("17", s.solve_part_one());
+        )
+        .unwrap();
+        assert_eq!(Answer::Integer(17), s.solve_part_one(&Context::default()));
+    }
+
+    #[test]
+    fn provided_example_part_two() {
+        let mut s = solver();
+        s.set_param("max_total_distance", 32);
+        s.presolve("1, 1\n1, 6\n8, 3\n3, 4\n5, 5\n8, 9\n").unwrap();
+        assert_eq!(Answer::Integer(16), s.solve_part_two(&Context::default()));
     }
 
     #[test]
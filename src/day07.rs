@@ -3,7 +3,34 @@ use std::{collections::BTreeSet, iter::repeat_n};
 use itertools::Itertools;
 use regex::Regex;
 
+use crate::context::Context;
+use crate::parse::ParseError;
+use crate::parse::captures;
+use crate::parse::input_lines;
+use crate::registry::REFERENCE;
+use crate::registry::SolverInfo;
+use crate::registry::Tag;
+use crate::solver::Answer;
+use crate::solver::Param;
 use crate::solver::Solver;
+use crate::solver::with_default_params;
+
+const PARAMS: &[Param] = &[
+    Param {
+        name: "worker_count",
+        description: "Number of workers, including you",
+        default: 5,
+        min: 1,
+        max: i64::MAX,
+    },
+    Param {
+        name: "work_time",
+        description: "Seconds each step takes on top of its letter's position",
+        default: 60,
+        min: 0,
+        max: i64::MAX,
+    },
+];
 
 #[derive(Default)]
 pub struct PuzzleSolver {
@@ -13,28 +40,23 @@ pub struct PuzzleSolver {
 }
 
 impl Solver for PuzzleSolver {
-    fn presolve(&mut self, input: &str) {
+    fn presolve(&mut self, input: &str) -> Result<(), ParseError> {
+        const EXPECTED: &str =
+            "an instruction such as Step C must be finished before step A can begin.";
         let re = Regex::new(r"Step (\w+) must be finished before step (\w+) can begin.").unwrap();
-        self.input = input
-            .trim()
-            .split("\n")
-            .map(|line| match re.captures(line) {
-                None => {
-                    panic!("unable to parse line: >{line}<");
-                }
-                Some(c) => c,
-            })
-            .map(|m| {
+        self.input = input_lines(input, EXPECTED)?
+            .into_iter()
+            .map(|(line_no, line)| {
+                let m = captures(&re, line_no, line, EXPECTED)?;
                 let prerequisite = m.get(1).unwrap().as_str().to_string();
                 let for_step = m.get(2).unwrap().as_str().to_string();
-                (prerequisite, for_step)
+                Ok((prerequisite, for_step))
             })
-            .collect();
-        self.worker_count = 5;
-        self.work_time = 60;
+            .collect::<Result<_, _>>()?;
+        Ok(())
     }
 
-    fn solve_part_one(&mut self) -> String {
+    fn solve_part_one(&mut self, _ctx: &Context) -> Answer {
         let mut remaining_edges = self.input.clone();
         let mut ready = BTreeSet::from_iter(
             self.input
@@ -63,10 +85,10 @@ impl Solver for PuzzleSolver {
                     .filter(|s| !operation_order.contains(s)),
             );
         }
-        operation_order.into_iter().join("")
+        Answer::Text(operation_order.into_iter().join(""))
     }
 
-    fn solve_part_two(&mut self) -> String {
+    fn solve_part_two(&mut self, ctx: &Context) -> Answer {
         let remaining_edges = self.input.clone();
         let all_nodes = BTreeSet::from_iter(
             self.input
@@ -91,7 +113,7 @@ impl Solver for PuzzleSolver {
         let mut finished_nodes: BTreeSet<&str> = BTreeSet::new();
         while worker_jobs.iter().any(|j| j.is_some()) {
             t += 1;
-            for job in worker_jobs.iter_mut() {
+            for (worker, job) in worker_jobs.iter_mut().enumerate() {
                 if job.is_none() {
                     continue;
                 }
@@ -101,6 +123,9 @@ impl Solver for PuzzleSolver {
                     + job_node.chars().next().unwrap() as isize
                     - 'A' as isize;
                 if job_finish_time <= t {
+                    ctx.explain(&format!("step {job_node} completed at {t}s"), || {
+                        format!("worker {}, started at {job_start_time}s", worker + 1)
+                    });
                     *job = None;
                     finished_nodes.insert(job_node);
                 }
@@ -122,12 +147,35 @@ impl Solver for PuzzleSolver {
                 }
             }
         }
-        t.to_string()
+        t.into()
+    }
+
+    fn params(&self) -> &'static [Param] {
+        PARAMS
+    }
+
+    fn set_param(&mut self, name: &str, value: i64) {
+        match name {
+            "worker_count" => self.worker_count = value as usize,
+            "work_time" => self.work_time = value as usize,
+            _ => unreachable!("unknown parameter {name}"),
+        }
     }
 }
 
 pub fn solver() -> PuzzleSolver {
-    PuzzleSolver::default()
+    with_default_params(PuzzleSolver::default())
+}
+
+inventory::submit! {
+    SolverInfo {
+        year: 2018,
+        day: 7,
+        implementation: REFERENCE,
+        title: "The Sum of Its Parts",
+        tags: &[Tag::Graph, Tag::Simulation],
+        make_solver: || Box::new(solver()),
+    }
 }
 
 #[cfg(test)]
@@ -147,8 +195,12 @@ Step B must be finished before step E can begin.
 Step D must be finished before step E can begin.
 Step F must be finished before step E can begin.
 ",
+        )
+        .unwrap();
+        assert_eq!(
+            Answer::Text("CABDFE".into()),
+            s.solve_part_one(&Context::default())
         );
-        assert_eq!("CABDFE", s.solve_part_one());
     }
 
     #[test]
@@ -163,9 +215,10 @@ Step B must be finished before step E can begin.
 Step D must be finished before step E can begin.
 Step F must be finished before step E can begin.
 ",
-        );
+        )
+        .unwrap();
         s.work_time = 0;
         s.worker_count = 2;
-        assert_eq!("15", s.solve_part_two());
+        assert_eq!(Answer::Integer(15), s.solve_part_two(&Context::default()));
     }
 }
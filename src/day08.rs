@@ -1,3 +1,11 @@
+use crate::context::Context;
+use crate::parse::ParseError;
+use crate::parse::input_line;
+use crate::parse::parse_at;
+use crate::registry::REFERENCE;
+use crate::registry::SolverInfo;
+use crate::registry::Tag;
+use crate::solver::Answer;
 use crate::solver::Solver;
 
 #[derive(Default)]
@@ -5,6 +13,19 @@ pub struct PuzzleSolver {
     input: Vec<isize>,
 }
 
+/// Returns the number of values taken by the tree at the start of `input`, or
+/// `None` if the input ends before the tree does.
+fn tree_size(input: &[isize]) -> Option<usize> {
+    let children_count = *input.first()?;
+    let metadata_count = *input.get(1)? as usize;
+    let mut size = 2;
+    for _ in 0..children_count {
+        size += tree_size(input.get(size..)?)?;
+    }
+    size = size.checked_add(metadata_count)?;
+    (size <= input.len()).then_some(size)
+}
+
 fn sum(input: &[isize]) -> (isize, usize) {
     let children_count = input[0];
     let metadata_count = input[1] as usize;
@@ -49,20 +70,33 @@ fn sum_b(input: &[isize]) -> (isize, usize) {
 }
 
 impl Solver for PuzzleSolver {
-    fn presolve(&mut self, input: &str) {
-        self.input = input
-            .trim()
-            .split(' ')
-            .map(|s| s.parse().unwrap())
-            .collect();
+    fn presolve(&mut self, input: &str) -> Result<(), ParseError> {
+        const EXPECTED: &str = "a list of numbers separated by spaces";
+        let (line_no, line) = input_line(input, EXPECTED)?;
+        let mut column = 1;
+        self.input = vec![];
+        for number in line.split(' ') {
+            self.input
+                .push(parse_at(line_no, column, number, "a non-negative number")?);
+            column += number.len() + 1;
+        }
+        if tree_size(&self.input) != Some(self.input.len()) {
+            return Err(ParseError::new(
+                line_no,
+                line.len() + 1,
+                "",
+                "the numbers of a complete tree",
+            ));
+        }
+        Ok(())
     }
 
-    fn solve_part_one(&mut self) -> String {
-        sum(self.input.as_slice()).0.to_string()
+    fn solve_part_one(&mut self, _ctx: &Context) -> Answer {
+        sum(self.input.as_slice()).0.into()
     }
 
-    fn solve_part_two(&mut self) -> String {
-        sum_b(self.input.as_slice()).0.to_string()
+    fn solve_part_two(&mut self, _ctx: &Context) -> Answer {
+        sum_b(self.input.as_slice()).0.into()
     }
 }
 
@@ -70,6 +104,17 @@ pub fn solver() -> PuzzleSolver {
     PuzzleSolver::default()
 }
 
+inventory::submit! {
+    SolverInfo {
+        year: 2018,
+        day: 8,
+        implementation: REFERENCE,
+        title: "Memory Maneuver",
+        tags: &[Tag::Tree],
+        make_solver: || Box::new(solver()),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -78,8 +123,31 @@ mod tests {
     #[test]
     fn provided_example() {
         let mut s = solver();
-        s.presolve("2 3 0 3 10 11 12 1 1 0 1 99 2 1 1 2");
-        assert_eq!("138", s.solve_part_one());
-        assert_eq!("66", s.solve_part_two());
+        s.presolve("2 3 0 3 10 11 12 1 1 0 1 99 2 1 1 2").unwrap();
+        assert_eq!(Answer::Integer(138), s.solve_part_one(&Context::default()));
+        assert_eq!(Answer::Integer(66), s.solve_part_two(&Context::default()));
+    }
+
+    #[test]
+    fn truncated_input() {
+        let mut s = solver();
+        assert_eq!(
+            s.presolve("2 3 0 3 10 11 12 1 1 0 1 99 2 1 1"),
+            Err(ParseError::new(1, 34, "", "the numbers of a complete tree"))
+        );
+    }
+
+    #[test]
+    fn numbers_on_more_lines() {
+        let mut s = solver();
+        assert_eq!(
+            s.presolve("2 3 0 3 10 11 12\n1 1 0 1 99 2 1 1 2\n"),
+            Err(ParseError::new(
+                2,
+                1,
+                "1 1 0 1 99 2 1 1 2",
+                "the end of the input"
+            ))
+        );
     }
 }
@@ -1,5 +1,14 @@
 use regex::Regex;
 
+use crate::context::Context;
+use crate::parse::ParseError;
+use crate::parse::captures;
+use crate::parse::input_line;
+use crate::parse::parse_group;
+use crate::registry::REFERENCE;
+use crate::registry::SolverInfo;
+use crate::registry::Tag;
+use crate::solver::Answer;
 use crate::solver::Solver;
 
 #[derive(Clone, Copy, Default)]
@@ -9,14 +18,17 @@ pub struct PuzzleSolver {
 }
 
 impl Solver for PuzzleSolver {
-    fn presolve(&mut self, input: &str) {
-        let re = Regex::new(r"\d+").unwrap();
-        let mut parse_input = re.find_iter(input);
-        self.player_count = parse_input.next().unwrap().as_str().parse().unwrap();
-        self.last_marble = parse_input.next().unwrap().as_str().parse().unwrap();
+    fn presolve(&mut self, input: &str) -> Result<(), ParseError> {
+        const EXPECTED: &str = "a game such as 10 players; last marble is worth 1618 points";
+        let re = Regex::new(r"(\d+) players; last marble is worth (\d+) points").unwrap();
+        let (line_no, line) = input_line(input, EXPECTED)?;
+        let c = captures(&re, line_no, line, EXPECTED)?;
+        self.player_count = parse_group(&c, 1, line_no, "a number of players")?;
+        self.last_marble = parse_group(&c, 2, line_no, "a marble value")?;
+        Ok(())
     }
 
-    fn solve_part_one(&mut self) -> String {
+    fn solve_part_one(&mut self, ctx: &Context) -> Answer {
         let mut player_scores = vec![0; self.player_count];
         let mut current_player = 0;
         let mut marbles_head = vec![0];
@@ -25,6 +37,9 @@ impl Solver for PuzzleSolver {
         let mut current_marble = 0;
         let mut marbles_count = 1;
         for marble in 1..=self.last_marble {
+            if marble % 100_000 == 0 {
+                ctx.progress(|| format!("marble {marble} of {}", self.last_marble));
+            }
             if marble % 23 != 0 {
                 let insert_index = (current_marble + 1) % marbles_count + 1;
                 // Insertion for dual-vector representation.
@@ -78,15 +93,15 @@ impl Solver for PuzzleSolver {
             }
             current_player = (current_player + 1) % self.player_count;
         }
-        player_scores.iter().max().unwrap().to_string()
+        (*player_scores.iter().max().unwrap()).into()
     }
 
-    fn solve_part_two(&mut self) -> String {
+    fn solve_part_two(&mut self, ctx: &Context) -> Answer {
         PuzzleSolver {
             last_marble: self.last_marble * 100,
             ..*self
         }
-        .solve_part_one()
+        .solve_part_one(ctx)
     }
 }
 
@@ -94,23 +109,52 @@ pub fn solver() -> PuzzleSolver {
     PuzzleSolver::default()
 }
 
+inventory::submit! {
+    SolverInfo {
+        year: 2018,
+        day: 9,
+        implementation: REFERENCE,
+        title: "Marble Mania",
+        tags: &[Tag::Simulation],
+        make_solver: || Box::new(solver()),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use std::sync::{Arc, Mutex};
     use yare::parameterized;
 
     #[parameterized(
-    example_1 = {"9 players; last marble is worth 25 points", "32"},
-    example_2 = {"10 players; last marble is worth 1618 points", "8317"},
-    example_3 = {"13 players; last marble is worth 7999 points", "146373"},
-    example_4 = {"17 players; last marble is worth 1104 points", "2764"},
-    example_5 = {"21 players; last marble is worth 6111 points", "54718"},
-    example_6 = {"30 players; last marble is worth 5807 points", "37305"},
+    example_1 = {"9 players; last marble is worth 25 points", 32},
+    example_2 = {"10 players; last marble is worth 1618 points", 8317},
+    example_3 = {"13 players; last marble is worth 7999 points", 146373},
+    example_4 = {"17 players; last marble is worth 1104 points", 2764},
+    example_5 = {"21 players; last marble is worth 6111 points", 54718},
+    example_6 = {"30 players; last marble is worth 5807 points", 37305},
 )]
     #[test_macro(test_log::test)]
-    fn provided_example(input: &str, expected_result: &str) {
+    fn provided_example(input: &str, expected_result: i64) {
+        let mut s = solver();
+        s.presolve(input).unwrap();
+        assert_eq!(
+            Answer::Integer(expected_result),
+            s.solve_part_one(&Context::default())
+        );
+    }
+
+    #[test]
+    fn part_two_reports_progress() {
+        let messages = Arc::new(Mutex::new(vec![]));
+        let ctx = Context::default().with_reporter({
+            let messages = messages.clone();
+            move |message| messages.lock().unwrap().push(message.to_string())
+        });
         let mut s = solver();
-        s.presolve(input);
-        assert_eq!(expected_result, s.solve_part_one());
+        s.presolve("10 players; last marble is worth 1618 points")
+            .unwrap();
+        s.solve_part_two(&ctx);
+        assert_eq!(messages.lock().unwrap()[0], "marble 100000 of 161800");
     }
 }
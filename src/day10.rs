@@ -1,14 +1,38 @@
 use std::collections::HashSet;
 
-use log::info;
+use itertools::Itertools;
+use log::debug;
 use regex::Regex;
 
+use crate::context::Context;
+use crate::parse::ParseError;
+use crate::parse::captures;
+use crate::parse::input_lines;
+use crate::parse::parse_group;
+use crate::registry::REFERENCE;
+use crate::registry::SolverInfo;
+use crate::registry::Tag;
+use crate::solver::Answer;
+use crate::solver::Param;
 use crate::solver::Solver;
+use crate::solver::with_default_params;
+use crate::visualize::Color;
+use crate::visualize::Frame;
+use crate::visualize::Visualize;
+
+const PARAMS: &[Param] = &[Param {
+    name: "max_diameter",
+    description: "Distance from the first point within which to look for the message",
+    default: 80,
+    min: 1,
+    max: i64::MAX,
+}];
 
 #[derive(Default)]
 pub struct PuzzleSolver {
     initial_positions: Vec<(i64, i64)>,
     velocities: Vec<(i64, i64)>,
+    max_diameter: i64,
 }
 
 fn diameter(positions: &[(i64, i64)]) -> i64 {
@@ -20,49 +44,91 @@ fn diameter(positions: &[(i64, i64)]) -> i64 {
         .unwrap()
 }
 
-fn print_screen(positions: &Vec<(i64, i64)>) {
+fn height(positions: &[(i64, i64)]) -> i64 {
+    let (min_y, max_y) = positions
+        .iter()
+        .map(|(_, y)| *y)
+        .minmax()
+        .into_option()
+        .unwrap();
+    max_y - min_y
+}
+
+/// Draws the points as `#` on a background of `.`, one line per row.
+fn render(positions: &[(i64, i64)]) -> String {
     let start_x = positions.iter().map(|(x, _)| *x).min().unwrap();
     let end_x = positions.iter().map(|(x, _)| *x).max().unwrap();
     let start_y = positions.iter().map(|(_, y)| *y).min().unwrap();
     let end_y = positions.iter().map(|(_, y)| *y).max().unwrap();
     let set: HashSet<_> = HashSet::from_iter(positions);
-    for y in start_y..=end_y {
-        info!(
-            "> {}",
-            String::from_iter((start_x..=end_x).map(|x| if set.contains(&(x, y)) {
-                '#'
-            } else {
-                '.'
-            }))
-        );
-    }
+    (start_y..=end_y)
+        .map(|y| {
+            String::from_iter(
+                (start_x..=end_x).map(|x| if set.contains(&(x, y)) { '#' } else { '.' }),
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
 }
 
-impl Solver for PuzzleSolver {
-    fn presolve(&mut self, input: &str) {
-        let re = Regex::new(
-            r"position=<\s*([-0-9]+),\s*([-0-9]+)> velocity=<\s*([-0-9]+),\s*([-0-9]+)>",
-        )
-        .unwrap();
-        for (_, [px, py, vx, vy]) in re.captures_iter(input).map(|capture| capture.extract()) {
-            self.initial_positions
-                .push((px.parse().unwrap(), py.parse().unwrap()));
-            self.velocities
-                .push((vx.parse().unwrap(), vy.parse().unwrap()));
+/// The points at one moment, drawn as stars on the smallest frame that holds
+/// them all.
+struct Sky<'a> {
+    positions: &'a [(i64, i64)],
+}
+
+impl Visualize for Sky<'_> {
+    fn frame(&self) -> Frame {
+        let (start_x, end_x) = self
+            .positions
+            .iter()
+            .map(|(x, _)| *x)
+            .minmax()
+            .into_option()
+            .unwrap();
+        let (start_y, end_y) = self
+            .positions
+            .iter()
+            .map(|(_, y)| *y)
+            .minmax()
+            .into_option()
+            .unwrap();
+        let mut frame = Frame::new(
+            start_x,
+            start_y,
+            (end_x - start_x + 1) as usize,
+            (end_y - start_y + 1) as usize,
+        );
+        for (x, y) in self.positions {
+            frame.set(*x, *y, '#', Some(Color::Yellow));
         }
+        frame
     }
+}
 
-    fn solve_part_one(&mut self) -> String {
+impl PuzzleSolver {
+    /// Returns the time at which the points spell out the message, and their
+    /// positions at that time. The points are close together for a few seconds
+    /// around the message, which is the moment when they are the least tall.
+    fn find_message(&self, ctx: &Context) -> (i64, Vec<(i64, i64)>) {
         let mut positions = self.initial_positions.clone();
-        let mut peak_achieved = false;
+        let mut message: Option<(i64, Vec<_>)> = None;
         let mut t = 0;
         loop {
-            if diameter(&positions) < 80 {
-                info!("t == {t}");
-                print_screen(&positions);
-                peak_achieved = true;
-            } else if peak_achieved {
-                break;
+            ctx.progress(|| format!("{t} seconds"));
+            if diameter(&positions) < self.max_diameter {
+                ctx.record(&Sky {
+                    positions: &positions,
+                });
+                debug!("t == {t}, height == {}", height(&positions));
+                if message
+                    .as_ref()
+                    .is_none_or(|(_, best)| height(&positions) < height(best))
+                {
+                    message = Some((t, positions.clone()));
+                }
+            } else if let Some(message) = message {
+                return message;
             }
             for ((px, py), (vx, vy)) in positions.iter_mut().zip(self.velocities.iter()) {
                 *px += *vx;
@@ -70,14 +136,122 @@ impl Solver for PuzzleSolver {
             }
             t += 1;
         }
-        "".into()
+    }
+}
+
+impl Solver for PuzzleSolver {
+    fn presolve(&mut self, input: &str) -> Result<(), ParseError> {
+        const EXPECTED: &str = "a point such as position=< 9,  1> velocity=< 0,  2>";
+        let re = Regex::new(
+            r"position=<\s*([-0-9]+),\s*([-0-9]+)> velocity=<\s*([-0-9]+),\s*([-0-9]+)>",
+        )
+        .unwrap();
+        for (line_no, line) in input_lines(input, EXPECTED)? {
+            let c = captures(&re, line_no, line, EXPECTED)?;
+            self.initial_positions.push((
+                parse_group(&c, 1, line_no, "an X position")?,
+                parse_group(&c, 2, line_no, "a Y position")?,
+            ));
+            self.velocities.push((
+                parse_group(&c, 3, line_no, "an X velocity")?,
+                parse_group(&c, 4, line_no, "a Y velocity")?,
+            ));
+        }
+        Ok(())
+    }
+
+    fn solve_part_one(&mut self, ctx: &Context) -> Answer {
+        let (_, positions) = self.find_message(ctx);
+        Answer::Art(render(&positions))
     }
 
-    fn solve_part_two(&mut self) -> String {
-        self.solve_part_one()
+    fn solve_part_two(&mut self, ctx: &Context) -> Answer {
+        self.find_message(ctx).0.into()
+    }
+
+    fn params(&self) -> &'static [Param] {
+        PARAMS
+    }
+
+    fn set_param(&mut self, name: &str, value: i64) {
+        match name {
+            "max_diameter" => self.max_diameter = value,
+            _ => unreachable!("unknown parameter {name}"),
+        }
     }
 }
 
 pub fn solver() -> PuzzleSolver {
-    PuzzleSolver::default()
+    with_default_params(PuzzleSolver::default())
+}
+
+inventory::submit! {
+    SolverInfo {
+        year: 2018,
+        day: 10,
+        implementation: REFERENCE,
+        title: "The Stars Align",
+        tags: &[Tag::Simulation, Tag::Grid],
+        make_solver: || Box::new(solver()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use test_log::test;
+
+    #[test]
+    fn provided_example() {
+        let mut s = solver();
+        s.presolve(
+            "position=< 9,  1> velocity=< 0,  2>
+position=< 7,  0> velocity=<-1,  0>
+position=< 3, -2> velocity=<-1,  1>
+position=< 6, 10> velocity=<-2, -1>
+position=< 2, -4> velocity=< 2,  2>
+position=<-6, 10> velocity=< 2, -2>
+position=< 1,  8> velocity=< 1, -1>
+position=< 1,  7> velocity=< 1,  0>
+position=<-3, 11> velocity=< 1, -2>
+position=< 7,  6> velocity=<-1, -1>
+position=<-2,  3> velocity=< 1,  0>
+position=<-4,  3> velocity=< 2,  0>
+position=<10, -3> velocity=<-1,  1>
+position=< 5, 11> velocity=< 1, -2>
+position=< 4,  7> velocity=< 0, -1>
+position=< 8, -2> velocity=< 0,  1>
+position=<15,  0> velocity=<-2,  0>
+position=< 1,  6> velocity=< 1,  0>
+position=< 8,  9> velocity=< 0, -1>
+position=< 3,  3> velocity=<-1,  1>
+position=< 0,  5> velocity=< 0, -1>
+position=<-2,  2> velocity=< 2,  0>
+position=< 5, -2> velocity=< 1,  2>
+position=< 1,  4> velocity=< 2,  1>
+position=<-2,  7> velocity=< 2, -2>
+position=< 3,  6> velocity=<-1, -1>
+position=< 5,  0> velocity=< 1,  0>
+position=<-6,  0> velocity=< 2,  0>
+position=< 5,  9> velocity=< 1, -2>
+position=<14,  7> velocity=<-2,  0>
+position=<-3,  6> velocity=< 2, -1>",
+        )
+        .unwrap();
+        assert_eq!(
+            Answer::Art(
+                "#...#..###
+#...#...#.
+#...#...#.
+#####...#.
+#...#...#.
+#...#...#.
+#...#...#.
+#...#..###"
+                    .into()
+            ),
+            s.solve_part_one(&Context::default())
+        );
+        assert_eq!(Answer::Integer(3), s.solve_part_two(&Context::default()));
+    }
 }
@@ -1,5 +1,18 @@
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::thread;
+use std::time::Duration;
+
 use array2d::Array2D;
 
+use crate::context;
+use crate::context::Context;
+use crate::parse::ParseError;
+use crate::parse::input_line;
+use crate::parse::parse_at;
+use crate::registry::REFERENCE;
+use crate::registry::SolverInfo;
+use crate::registry::Tag;
+use crate::solver::Answer;
 use crate::solver::Solver;
 
 pub struct PuzzleSolver {
@@ -8,8 +21,10 @@ pub struct PuzzleSolver {
 }
 
 impl Solver for PuzzleSolver {
-    fn presolve(&mut self, input: &str) {
-        self.serial_no = input.trim().parse().unwrap();
+    fn presolve(&mut self, input: &str) -> Result<(), ParseError> {
+        const EXPECTED: &str = "a grid serial number";
+        let (line_no, line) = input_line(input, EXPECTED)?;
+        self.serial_no = parse_at(line_no, 1, line, EXPECTED)?;
         for cell_x in 1usize..=300 {
             let rack_id = cell_x + 10;
             for cell_y in 1usize..=300 {
@@ -51,9 +66,10 @@ impl Solver for PuzzleSolver {
                 }
             }
         }
+        Ok(())
     }
 
-    fn solve_part_one(&mut self) -> String {
+    fn solve_part_one(&mut self, _ctx: &Context) -> Answer {
         let mut max_power_level = i64::MIN;
         let mut max_power_level_block = None;
         for block_x in 1..=298 {
@@ -69,32 +85,45 @@ impl Solver for PuzzleSolver {
             }
         }
         let (x, y) = max_power_level_block.unwrap();
-        format!("{},{}", x, y)
+        Answer::Coordinates(vec![x as i64, y as i64])
     }
 
-    fn solve_part_two(&mut self) -> String {
+    fn solve_part_two(&mut self, ctx: &Context) -> Answer {
         let mut max_power_level = i64::MIN;
         let mut max_power_level_block = None;
         for block_x in 1..=300 {
-            for block_y in 1..=300 {
-                for block_size in 1..=300 {
-                    if block_x + block_size > 301 || block_y + block_size > 301 {
-                        continue;
-                    }
-                    let block_power_level = self.cumulative_power_levels
-                        [(block_x + block_size - 1, block_y + block_size - 1)]
-                        - self.cumulative_power_levels[(block_x - 1, block_y + block_size - 1)]
-                        - self.cumulative_power_levels[(block_x + block_size - 1, block_y - 1)]
-                        + self.cumulative_power_levels[(block_x - 1, block_y - 1)];
-                    if block_power_level > max_power_level {
-                        max_power_level = block_power_level;
-                        max_power_level_block = Some((block_x, block_y, block_size));
-                    }
-                }
+            ctx.progress(|| format!("column {block_x} of 300"));
+            if let Some((power_level, block)) = self.best_block_in_column(block_x)
+                && power_level > max_power_level
+            {
+                max_power_level = power_level;
+                max_power_level_block = Some(block);
             }
         }
         let (x, y, size) = max_power_level_block.unwrap();
-        format!("{},{},{}", x, y, size)
+        Answer::Coordinates(vec![x as i64, y as i64, size as i64])
+    }
+}
+
+impl PuzzleSolver {
+    /// Returns the power level, position and size of the most powerful block
+    /// whose left edge is in the given column. Ties go to the smallest `y` and
+    /// then the smallest size.
+    fn best_block_in_column(&self, block_x: usize) -> Option<(i64, (usize, usize, usize))> {
+        let mut best = None;
+        for block_y in 1..=300 {
+            for block_size in 1..=(301 - block_x.max(block_y)) {
+                let block_power_level = self.cumulative_power_levels
+                    [(block_x + block_size - 1, block_y + block_size - 1)]
+                    - self.cumulative_power_levels[(block_x - 1, block_y + block_size - 1)]
+                    - self.cumulative_power_levels[(block_x + block_size - 1, block_y - 1)]
+                    + self.cumulative_power_levels[(block_x - 1, block_y - 1)];
+                if best.is_none_or(|(max, _)| block_power_level > max) {
+                    best = Some((block_power_level, (block_x, block_y, block_size)));
+                }
+            }
+        }
+        best
     }
 }
 
@@ -105,6 +134,92 @@ pub fn solver() -> PuzzleSolver {
     }
 }
 
+/// Searches the columns for the most powerful block on every core.
+pub struct ParallelSolver(PuzzleSolver);
+
+impl Solver for ParallelSolver {
+    fn presolve(&mut self, input: &str) -> Result<(), ParseError> {
+        self.0.presolve(input)
+    }
+
+    fn solve_part_one(&mut self, ctx: &Context) -> Answer {
+        self.0.solve_part_one(ctx)
+    }
+
+    fn solve_part_two(&mut self, ctx: &Context) -> Answer {
+        let solver = &self.0;
+        let workers = thread::available_parallelism().map_or(1, |n| n.get());
+        let next_column = AtomicUsize::new(1);
+        let columns_done = AtomicUsize::new(0);
+        // The context cannot be shared with the workers, so they only look at
+        // whether they should stop, and progress is reported from here.
+        let cancellation = ctx.cancellation();
+        let bests = thread::scope(|scope| {
+            let handles: Vec<_> = (0..workers)
+                .map(|_| {
+                    scope.spawn(|| {
+                        let mut bests = vec![];
+                        loop {
+                            let block_x = next_column.fetch_add(1, Ordering::Relaxed);
+                            if block_x > 300
+                                || cancellation.load(Ordering::Relaxed)
+                                || context::is_interrupted()
+                            {
+                                return bests;
+                            }
+                            bests.extend(solver.best_block_in_column(block_x));
+                            columns_done.fetch_add(1, Ordering::Relaxed);
+                        }
+                    })
+                })
+                .collect();
+            // Workers that have been cancelled stop early, so progress is
+            // reported once more after they are done to find out.
+            loop {
+                let done = columns_done.load(Ordering::Relaxed);
+                ctx.progress(|| format!("{done} of 300 columns"));
+                if handles.iter().all(|handle| handle.is_finished()) {
+                    break;
+                }
+                thread::sleep(Duration::from_millis(10));
+            }
+            handles
+                .into_iter()
+                .flat_map(|handle| handle.join().unwrap())
+                .collect::<Vec<_>>()
+        });
+        // Break ties like the reference implementation, which goes through
+        // the columns in order.
+        let (_, (x, y, size)) = bests
+            .into_iter()
+            .max_by_key(|&(power_level, (x, _, _))| (power_level, -(x as i64)))
+            .unwrap();
+        Answer::Coordinates(vec![x as i64, y as i64, size as i64])
+    }
+}
+
+inventory::submit! {
+    SolverInfo {
+        year: 2018,
+        day: 11,
+        implementation: "parallel",
+        title: "Chronal Charge",
+        tags: &[Tag::Grid],
+        make_solver: || Box::new(ParallelSolver(solver())),
+    }
+}
+
+inventory::submit! {
+    SolverInfo {
+        year: 2018,
+        day: 11,
+        implementation: REFERENCE,
+        title: "Chronal Charge",
+        tags: &[Tag::Grid],
+        make_solver: || Box::new(solver()),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -113,16 +228,38 @@ mod tests {
     #[test]
     fn provided_example_1() {
         let mut s = solver();
-        s.presolve("18");
-        assert_eq!("33,45", s.solve_part_one());
-        assert_eq!("90,269,16", s.solve_part_two());
+        s.presolve("18").unwrap();
+        assert_eq!(
+            Answer::Coordinates(vec![33, 45]),
+            s.solve_part_one(&Context::default())
+        );
+        assert_eq!(
+            Answer::Coordinates(vec![90, 269, 16]),
+            s.solve_part_two(&Context::default())
+        );
     }
 
     #[test]
     fn provided_example_2() {
         let mut s = solver();
-        s.presolve("42");
-        assert_eq!("21,61", s.solve_part_one());
-        assert_eq!("232,251,12", s.solve_part_two());
+        s.presolve("42").unwrap();
+        assert_eq!(
+            Answer::Coordinates(vec![21, 61]),
+            s.solve_part_one(&Context::default())
+        );
+        assert_eq!(
+            Answer::Coordinates(vec![232, 251, 12]),
+            s.solve_part_two(&Context::default())
+        );
+    }
+
+    #[test]
+    fn parallel_solver_agrees() {
+        let mut s = ParallelSolver(solver());
+        s.presolve("18").unwrap();
+        assert_eq!(
+            Answer::Coordinates(vec![90, 269, 16]),
+            s.solve_part_two(&Context::default())
+        );
     }
 }
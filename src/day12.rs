@@ -1,32 +1,106 @@
 use std::collections::HashSet;
 
+use regex::Regex;
+
+use crate::context::Context;
+use crate::parse::ParseError;
+use crate::parse::captures;
+use crate::parse::input_lines;
+use crate::registry::REFERENCE;
+use crate::registry::SolverInfo;
+use crate::registry::Tag;
+use crate::solver::Answer;
+use crate::solver::Param;
 use crate::solver::Solver;
+use crate::solver::with_default_params;
+use crate::visualize::Color;
+use crate::visualize::Frame;
+use crate::visualize::Visualize;
+
+const PARAMS: &[Param] = &[
+    Param {
+        name: "part_one_generations",
+        description: "Number of generations to simulate in part one",
+        default: 20,
+        min: 0,
+        max: i64::MAX,
+    },
+    Param {
+        name: "part_two_generations",
+        description: "Number of generations to simulate in part two",
+        default: 50_000_000_000,
+        min: 0,
+        max: i64::MAX,
+    },
+];
+
+/// How many generations a frame shows.
+const VISIBLE_GENERATIONS: usize = 40;
+
+/// The plants of the last few generations, one generation per row, like in
+/// the puzzle description.
+struct Pots<'a> {
+    generations: &'a [HashSet<isize>],
+}
+
+impl Visualize for Pots<'_> {
+    fn frame(&self) -> Frame {
+        let plants = || self.generations.iter().flatten();
+        let start = plants().min().copied().unwrap_or(0) as i64;
+        let end = plants().max().copied().unwrap_or(0) as i64;
+        let mut frame = Frame::new(start, 0, (end - start + 1) as usize, self.generations.len());
+        for (y, generation) in self.generations.iter().enumerate() {
+            for x in start..=end {
+                if generation.contains(&(x as isize)) {
+                    frame.set(x, y as i64, '#', Some(Color::Green));
+                } else {
+                    frame.set(x, y as i64, '.', None);
+                }
+            }
+        }
+        frame
+    }
+}
+
+/// Records the new generation of a simulation, if frames are being recorded.
+fn record(ctx: &Context, generations: &mut Vec<HashSet<isize>>, state: &HashSet<isize>) {
+    if ctx.is_recording() {
+        generations.push(state.clone());
+        let first = generations.len().saturating_sub(VISIBLE_GENERATIONS);
+        ctx.record(&Pots {
+            generations: &generations[first..],
+        });
+    }
+}
 
 #[derive(Default)]
 pub struct PuzzleSolver {
     initial_state: String,
     rules: HashSet<String>,
+    part_one_generations: i64,
+    part_two_generations: i64,
 }
 
 impl Solver for PuzzleSolver {
-    fn presolve(&mut self, input: &str) {
-        let mut lines = input.lines();
-        self.initial_state = lines
-            .next()
-            .unwrap()
-            .chars()
-            .skip("initial state: ".len())
-            .collect();
-        lines.next();
-        for rule_line in lines {
-            if rule_line.chars().nth("..... => ".len()).unwrap() != '#' {
-                continue;
+    fn presolve(&mut self, input: &str) -> Result<(), ParseError> {
+        const EXPECTED_STATE: &str = "an initial state such as initial state: #..#.#";
+        const EXPECTED_RULE: &str = "a rule such as ..#.. => #";
+        let state_re = Regex::new(r"^initial state: ([#.]+)$").unwrap();
+        let rule_re = Regex::new(r"^([#.]{5}) => ([#.])$").unwrap();
+        let lines = input_lines(input, EXPECTED_STATE)?;
+        let (line_no, line) = lines[0];
+        let state = captures(&state_re, line_no, line, EXPECTED_STATE)?;
+        self.initial_state = state[1].to_string();
+        for &(line_no, line) in lines.iter().skip(1) {
+            let rule = captures(&rule_re, line_no, line, EXPECTED_RULE)?;
+            if &rule[2] == "#" {
+                self.rules.insert(rule[1].to_string());
             }
-            self.rules.insert(rule_line.chars().take(5).collect());
         }
+        Ok(())
     }
 
-    fn solve_part_one(&mut self) -> String {
+    fn solve_part_one(&mut self, ctx: &Context) -> Answer {
         let mut state: HashSet<isize> = self
             .initial_state
             .chars()
@@ -34,7 +108,9 @@ impl Solver for PuzzleSolver {
             .filter(|(_, ch)| *ch == '#')
             .map(|(no, _)| no as isize)
             .collect();
-        for _ in 0..20 {
+        let mut generations = vec![];
+        record(ctx, &mut generations, &state);
+        for _ in 0..self.part_one_generations {
             let minimum_position = state.iter().min().unwrap() - 2;
             let maximum_position = state.iter().max().unwrap() + 2;
             let new_state = (minimum_position..=maximum_position)
@@ -48,11 +124,12 @@ impl Solver for PuzzleSolver {
                 })
                 .collect();
             state = new_state;
+            record(ctx, &mut generations, &state);
         }
-        state.iter().sum::<isize>().to_string()
+        state.iter().sum::<isize>().into()
     }
 
-    fn solve_part_two(&mut self) -> String {
+    fn solve_part_two(&mut self, ctx: &Context) -> Answer {
         let mut state: HashSet<isize> = self
             .initial_state
             .chars()
@@ -60,8 +137,11 @@ impl Solver for PuzzleSolver {
             .filter(|(_, ch)| *ch == '#')
             .map(|(no, _)| no as isize)
             .collect();
-        let total_steps = 50_000_000_000i64;
+        let total_steps = self.part_two_generations;
+        let mut generations = vec![];
+        record(ctx, &mut generations, &state);
         for step in 0..total_steps {
+            ctx.progress(|| format!("generation {step}"));
             let minimum_position = state.iter().min().unwrap() - 2;
             let maximum_position = state.iter().max().unwrap() + 2;
             let new_state = (minimum_position..=maximum_position)
@@ -74,6 +154,7 @@ impl Solver for PuzzleSolver {
                     self.rules.contains(&pattern)
                 })
                 .collect();
+            record(ctx, &mut generations, &new_state);
 
             // In this puzzle, a semi-stable state is achieved after a certain (~200) number of steps.
             // After this happens, the next state is always the previous state shifted to the right
@@ -93,12 +174,35 @@ impl Solver for PuzzleSolver {
             }
             state = new_state;
         }
-        state.iter().sum::<isize>().to_string()
+        state.iter().sum::<isize>().into()
+    }
+
+    fn params(&self) -> &'static [Param] {
+        PARAMS
+    }
+
+    fn set_param(&mut self, name: &str, value: i64) {
+        match name {
+            "part_one_generations" => self.part_one_generations = value,
+            "part_two_generations" => self.part_two_generations = value,
+            _ => unreachable!("unknown parameter {name}"),
+        }
     }
 }
 
 pub fn solver() -> PuzzleSolver {
-    Default::default()
+    with_default_params(PuzzleSolver::default())
+}
+
+inventory::submit! {
+    SolverInfo {
+        year: 2018,
+        day: 12,
+        implementation: REFERENCE,
+        title: "Subterranean Sustainability",
+        tags: &[Tag::Simulation],
+        make_solver: || Box::new(solver()),
+    }
 }
 
 #[cfg(test)]
@@ -127,7 +231,8 @@ mod tests {
 ###.# => #
 ####. => #
 ",
-        );
-        assert_eq!("325", s.solve_part_one());
+        )
+        .unwrap();
+        assert_eq!(Answer::Integer(325), s.solve_part_one(&Context::default()));
     }
 }
@@ -2,7 +2,17 @@ use std::collections::BTreeMap;
 
 use array2d::Array2D;
 
+use crate::context::Context;
+use crate::parse::ParseError;
+use crate::parse::input_lines;
+use crate::registry::REFERENCE;
+use crate::registry::SolverInfo;
+use crate::registry::Tag;
+use crate::solver::Answer;
 use crate::solver::Solver;
+use crate::visualize::Color;
+use crate::visualize::Frame;
+use crate::visualize::Visualize;
 
 #[derive(Clone, Copy, Debug)]
 enum Direction {
@@ -12,13 +22,53 @@ enum Direction {
     Right,
 }
 
+impl Direction {
+    fn symbol(self) -> char {
+        match self {
+            Direction::Up => '^',
+            Direction::Down => 'v',
+            Direction::Left => '<',
+            Direction::Right => '>',
+        }
+    }
+}
+
+/// Carts, keyed by their (y, x) position, with their direction and the number
+/// of intersections they have crossed.
+type Carts = BTreeMap<(usize, usize), (Direction, usize)>;
+
+/// The tracks with the carts on them, and where they crashed if they did.
+struct Tracks<'a> {
+    map: &'a Array2D<char>,
+    carts: &'a Carts,
+    crash: Option<(usize, usize)>,
+}
+
+impl Visualize for Tracks<'_> {
+    fn frame(&self) -> Frame {
+        let mut frame = Frame::new(0, 0, self.map.num_columns(), self.map.num_rows());
+        for ((y, x), ch) in self.map.enumerate_row_major() {
+            frame.set(x as i64, y as i64, *ch, None);
+        }
+        for ((y, x), (dir, _)) in self.carts {
+            frame.set(*x as i64, *y as i64, dir.symbol(), Some(Color::Green));
+        }
+        if let Some((y, x)) = self.crash {
+            frame.set(x as i64, y as i64, 'X', Some(Color::Red));
+        }
+        frame
+    }
+}
+
 pub struct PuzzleSolver {
     map: Array2D<char>,
     initial_positions: Vec<(usize, usize, Direction)>,
 }
 
 impl Solver for PuzzleSolver {
-    fn presolve(&mut self, input: &str) {
+    fn presolve(&mut self, input: &str) -> Result<(), ParseError> {
+        const EXPECTED: &str = "a track piece (one of /\\-|+), a cart (one of ^v<>) or a space";
+        input_lines(input, EXPECTED)?;
         let height = input.lines().count();
         let width = input.lines().map(|l| l.len()).max().unwrap();
         self.map = Array2D::filled_with(' ', height, width);
@@ -45,21 +95,37 @@ impl Solver for PuzzleSolver {
                         self.initial_positions.push((x, y, Direction::Right));
                     }
                     ' ' => {}
-                    _ => panic!("unexpected character >{ch}<"),
+                    _ => {
+                        return Err(ParseError::new(
+                            y + 1,
+                            x + 1,
+                            ch.to_string().as_str(),
+                            EXPECTED,
+                        ));
+                    }
                 }
             }
         }
+        Ok(())
     }
 
-    fn solve_part_one(&mut self) -> String {
-        let mut state: BTreeMap<(usize, usize), (Direction, usize)> = self
+    fn solve_part_one(&mut self, ctx: &Context) -> Answer {
+        let mut state: Carts = self
             .initial_positions
             .iter()
             .map(|(x, y, dir)| ((*y, *x), (*dir, 0)))
             .collect();
+        let mut tick = 0;
         loop {
+            tick += 1;
+            ctx.progress(|| format!("tick {tick}, {} carts", state.len()));
+            ctx.record(&Tracks {
+                map: &self.map,
+                carts: &state,
+                crash: None,
+            });
             let mut new_state = BTreeMap::new();
-            for ((cart_y, cart_x), (cart_dir, cart_turn_count)) in state.into_iter() {
+            for (&(cart_y, cart_x), &(cart_dir, cart_turn_count)) in state.iter() {
                 let (new_x, new_y, new_dir) =
                     match (self.map[(cart_y, cart_x)], cart_dir, cart_turn_count % 3) {
                         ('|', Direction::Up, _) => (cart_x, cart_y - 1, cart_dir),
@@ -95,7 +161,17 @@ impl Solver for PuzzleSolver {
                         0
                     };
                 if new_state.contains_key(&(new_y, new_x)) {
-                    return format!("{new_x},{new_y}");
+                    if ctx.is_recording() {
+                        // Carts that have not moved yet are still in the old state.
+                        let mut carts = new_state.clone();
+                        carts.extend(state.range((cart_y, cart_x)..).skip(1));
+                        ctx.record(&Tracks {
+                            map: &self.map,
+                            carts: &carts,
+                            crash: Some((new_y, new_x)),
+                        });
+                    }
+                    return Answer::Coordinates(vec![new_x as i64, new_y as i64]);
                 }
                 new_state.insert((new_y, new_x), (new_dir, new_turn_count));
             }
@@ -103,13 +179,21 @@ impl Solver for PuzzleSolver {
         }
     }
 
-    fn solve_part_two(&mut self) -> String {
-        let mut state: BTreeMap<(usize, usize), (Direction, usize)> = self
+    fn solve_part_two(&mut self, ctx: &Context) -> Answer {
+        let mut state: Carts = self
             .initial_positions
             .iter()
             .map(|(x, y, dir)| ((*y, *x), (*dir, 0)))
             .collect();
+        let mut tick = 0;
         loop {
+            tick += 1;
+            ctx.progress(|| format!("tick {tick}, {} carts", state.len()));
+            ctx.record(&Tracks {
+                map: &self.map,
+                carts: &state,
+                crash: None,
+            });
             let mut new_state = BTreeMap::new();
             for ((cart_y, cart_x), (cart_dir, cart_turn_count)) in state.into_iter() {
                 if new_state.remove(&(cart_y, cart_x)).is_some() {
@@ -156,7 +240,7 @@ impl Solver for PuzzleSolver {
             state = new_state;
             if state.len() == 1 {
                 let (cart_y, cart_x) = state.into_keys().next().unwrap();
-                return format!("{cart_x},{cart_y}");
+                return Answer::Coordinates(vec![cart_x as i64, cart_y as i64]);
             }
         }
     }
@@ -169,11 +253,24 @@ pub fn solver() -> PuzzleSolver {
     }
 }
 
+inventory::submit! {
+    SolverInfo {
+        year: 2018,
+        day: 13,
+        implementation: REFERENCE,
+        title: "Mine Cart Madness",
+        tags: &[Tag::Simulation, Tag::Grid],
+        make_solver: || Box::new(solver()),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use test_log::test;
 
+    use crate::testing::assert_cancellable;
+
     #[test]
     fn provided_example_1() {
         let mut s = solver();
@@ -184,8 +281,12 @@ mod tests {
 | | |  | v  |
 \-+-/  \-+--/
   \------/   ",
+        )
+        .unwrap();
+        assert_eq!(
+            Answer::Coordinates(vec![7, 3]),
+            s.solve_part_one(&Context::default())
         );
-        assert_eq!("7,3", s.solve_part_one());
     }
 
     #[test]
@@ -199,7 +300,20 @@ mod tests {
 \>+</ |
   |   ^
   \<->/",
+        )
+        .unwrap();
+        assert_eq!(
+            Answer::Coordinates(vec![6, 4]),
+            s.solve_part_two(&Context::default())
         );
-        assert_eq!("6,4", s.solve_part_two());
+    }
+
+    #[test]
+    fn carts_that_never_collide_are_cancelled() {
+        // Both carts go round clockwise, always half a loop apart.
+        let mut s = solver();
+        s.presolve("/->-\\\n|   |\n\\-<-/\n").unwrap();
+        assert_cancellable(|ctx| s.solve_part_one(ctx));
+        assert_cancellable(|ctx| s.solve_part_two(ctx));
     }
 }
@@ -1,3 +1,11 @@
+use crate::context::Context;
+use crate::parse::ParseError;
+use crate::parse::input_line;
+use crate::parse::parse_at;
+use crate::registry::REFERENCE;
+use crate::registry::SolverInfo;
+use crate::registry::Tag;
+use crate::solver::Answer;
 use crate::solver::Solver;
 
 pub struct PuzzleSolver {
@@ -5,11 +13,14 @@ pub struct PuzzleSolver {
 }
 
 impl Solver for PuzzleSolver {
-    fn presolve(&mut self, input: &str) {
-        self.recipes = input.trim().parse().unwrap();
+    fn presolve(&mut self, input: &str) -> Result<(), ParseError> {
+        const EXPECTED: &str = "a number of recipes";
+        let (line_no, line) = input_line(input, EXPECTED)?;
+        self.recipes = parse_at(line_no, 1, line, EXPECTED)?;
+        Ok(())
     }
 
-    fn solve_part_one(&mut self) -> String {
+    fn solve_part_one(&mut self, _ctx: &Context) -> Answer {
         let mut data = vec![3u8, 7u8];
         let mut elf1 = 0;
         let mut elf2 = 1;
@@ -24,17 +35,20 @@ impl Solver for PuzzleSolver {
             elf1 = (elf1 + 1usize + data[elf1] as usize) % data.len();
             elf2 = (elf2 + 1usize + data[elf2] as usize) % data.len();
         }
-        String::from_utf8(
-            data.iter()
-                .skip(self.recipes)
-                .take(10)
-                .map(|v| b'0' + v)
-                .collect(),
+        // Scores may start with a zero, so they are text rather than a number.
+        Answer::Text(
+            String::from_utf8(
+                data.iter()
+                    .skip(self.recipes)
+                    .take(10)
+                    .map(|v| b'0' + v)
+                    .collect(),
+            )
+            .unwrap(),
         )
-        .unwrap()
     }
 
-    fn solve_part_two(&mut self) -> String {
+    fn solve_part_two(&mut self, ctx: &Context) -> Answer {
         let string = {
             let mut digits = vec![];
             let mut remainder = self.recipes;
@@ -56,10 +70,15 @@ impl Solver for PuzzleSolver {
                     .zip(data[next_to_search..next_to_search + string.len()].iter())
                     .all(|(a, b)| *a == *b)
                 {
-                    return next_to_search.to_string();
+                    return next_to_search.into();
                 }
                 next_to_search += 1;
             }
+            // Recipes are added one or two at a time, so this still catches
+            // every multiple of 100 000.
+            if data.len() % 100_000 < 2 {
+                ctx.progress(|| format!("{} recipes", data.len()));
+            }
             let sum = data[elf1] + data[elf2];
             if sum > 9 {
                 data.push(1);
@@ -77,6 +96,17 @@ pub fn solver() -> PuzzleSolver {
     PuzzleSolver { recipes: 0 }
 }
 
+inventory::submit! {
+    SolverInfo {
+        year: 2018,
+        day: 14,
+        implementation: REFERENCE,
+        title: "Chocolate Charts",
+        tags: &[Tag::Simulation],
+        make_solver: || Box::new(solver()),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -85,49 +115,61 @@ mod tests {
     #[test]
     fn provided_example_1() {
         let mut s = solver();
-        s.presolve("9");
-        assert_eq!("5158916779", s.solve_part_one());
+        s.presolve("9").unwrap();
+        assert_eq!(
+            Answer::Text("5158916779".into()),
+            s.solve_part_one(&Context::default())
+        );
     }
 
     #[test]
     fn provided_example_2() {
         let mut s = solver();
-        s.presolve("5");
-        assert_eq!("0124515891", s.solve_part_one());
+        s.presolve("5").unwrap();
+        assert_eq!(
+            Answer::Text("0124515891".into()),
+            s.solve_part_one(&Context::default())
+        );
     }
 
     #[test]
     fn provided_example_3() {
         let mut s = solver();
-        s.presolve("18");
-        assert_eq!("9251071085", s.solve_part_one());
+        s.presolve("18").unwrap();
+        assert_eq!(
+            Answer::Text("9251071085".into()),
+            s.solve_part_one(&Context::default())
+        );
     }
 
     #[test]
     fn provided_example_4() {
         let mut s = solver();
-        s.presolve("2018");
-        assert_eq!("5941429882", s.solve_part_one());
+        s.presolve("2018").unwrap();
+        assert_eq!(
+            Answer::Text("5941429882".into()),
+            s.solve_part_one(&Context::default())
+        );
     }
 
     #[test]
     fn provided_example_5() {
         let mut s = solver();
-        s.presolve("51589");
-        assert_eq!("9", s.solve_part_two());
+        s.presolve("51589").unwrap();
+        assert_eq!(Answer::Integer(9), s.solve_part_two(&Context::default()));
     }
 
     #[test]
     fn provided_example_7() {
         let mut s = solver();
-        s.presolve("92510");
-        assert_eq!("18", s.solve_part_two());
+        s.presolve("92510").unwrap();
+        assert_eq!(Answer::Integer(18), s.solve_part_two(&Context::default()));
     }
 
     #[test]
     fn provided_example_8() {
         let mut s = solver();
-        s.presolve("59414");
-        assert_eq!("2018", s.solve_part_two());
+        s.presolve("59414").unwrap();
+        assert_eq!(Answer::Integer(2018), s.solve_part_two(&Context::default()));
     }
 }
@@ -1,12 +1,43 @@
 use std::collections::{BTreeSet, HashMap};
 
+use crate::context::Context;
+use crate::parse::ParseError;
+use crate::parse::input_lines;
+use crate::registry::REFERENCE;
+use crate::registry::SolverInfo;
+use crate::registry::Tag;
+use crate::solver::Answer;
+use crate::solver::Param;
 use crate::solver::Solver;
+use crate::solver::with_default_params;
+use crate::visualize::Color;
+use crate::visualize::Frame;
+use crate::visualize::Visualize;
+
+const PARAMS: &[Param] = &[
+    Param {
+        name: "goblin_attack_power",
+        description: "Attack power of every goblin",
+        default: 3,
+        min: 0,
+        max: i16::MAX as i64,
+    },
+    Param {
+        name: "hit_points",
+        description: "Hit points every unit starts with",
+        default: 200,
+        min: 1,
+        max: i16::MAX as i64,
+    },
+];
 
 #[derive(Default)]
 pub struct PuzzleSolver {
     goblins: Vec<(usize, usize)>,
     elves: Vec<(usize, usize)>,
     spaces: Vec<(usize, usize)>,
+    goblin_attack_power: i16,
+    hit_points: i16,
 }
 
 #[derive(PartialEq, PartialOrd, Eq, Ord, Clone, Debug, Hash)]
@@ -92,22 +123,57 @@ struct Unit {
     hp: i16,
 }
 
+/// The cavern in the middle of a battle.
+struct Battle<'a> {
+    positions: &'a HashMap<Coords, Option<usize>>,
+    units: &'a [Unit],
+}
+
+impl Visualize for Battle<'_> {
+    fn frame(&self) -> Frame {
+        // Open spaces are surrounded by walls, which are not in `positions`.
+        let width = self.positions.keys().map(|c| c.x).max().unwrap_or(0) + 2;
+        let height = self.positions.keys().map(|c| c.y).max().unwrap_or(0) + 2;
+        let mut frame = Frame::new(0, 0, width, height);
+        for y in 0..height {
+            for x in 0..width {
+                frame.set(x as i64, y as i64, '#', None);
+            }
+        }
+        for (coords, unit_ref) in self.positions {
+            let (ch, color) = match unit_ref.map(|unit_ref| self.units[unit_ref].faction) {
+                None => ('.', None),
+                Some(Faction::Elf) => ('E', Some(Color::Green)),
+                Some(Faction::Goblin) => ('G', Some(Color::Red)),
+            };
+            frame.set(coords.x as i64, coords.y as i64, ch, color);
+        }
+        frame
+    }
+}
+
 impl PuzzleSolver {
-    fn simulate_battle(&self, elf_attack_power: i16) -> (Faction, String) {
+    /// Fights the battle, and returns where an elf that got killed started,
+    /// if any, along with the outcome.
+    fn simulate_battle(
+        &self,
+        elf_attack_power: i16,
+        ctx: &Context,
+    ) -> (Option<(usize, usize)>, Answer) {
         let mut units = vec![];
         let mut positions: HashMap<Coords, Option<usize>> = HashMap::new();
         for (x, y) in self.elves.iter().copied() {
             positions.insert(Coords { x, y }, Some(units.len()));
             units.push(Unit {
                 faction: Faction::Elf,
-                hp: 200,
+                hp: self.hit_points,
             });
         }
         for (x, y) in self.goblins.iter().copied() {
             positions.insert(Coords { x, y }, Some(units.len()));
             units.push(Unit {
                 faction: Faction::Goblin,
-                hp: 200,
+                hp: self.hit_points,
             });
         }
         for (x, y) in self.spaces.iter().copied() {
@@ -127,6 +193,11 @@ impl PuzzleSolver {
         };
         let mut rounds = 0;
         'outerloop: loop {
+            ctx.progress(|| format!("attack power {elf_attack_power}, round {rounds}"));
+            ctx.record(&Battle {
+                positions: &positions,
+                units: &units,
+            });
             let mut act_order: Vec<_> = positions
                 .iter()
                 .filter_map(|(coords, unit_ref)| {
@@ -205,7 +276,7 @@ impl PuzzleSolver {
                     let enemy_ref = positions[enemy_position].unwrap();
                     let attack_power = match faction {
                         Faction::Elf => elf_attack_power,
-                        Faction::Goblin => 3,
+                        Faction::Goblin => self.goblin_attack_power,
                     };
                     units[enemy_ref].hp -= attack_power;
                     if units[enemy_ref].hp <= 0 {
@@ -215,27 +286,42 @@ impl PuzzleSolver {
             }
             rounds += 1;
         }
-        let outcome = (rounds
-            * units
-                .iter()
-                .map(|unit| if unit.hp > 0 { unit.hp as i32 } else { 0 })
-                .sum::<i32>())
-        .to_string();
-        let winning_faction = if units
+        ctx.record(&Battle {
+            positions: &positions,
+            units: &units,
+        });
+        let hit_points_left = units
             .iter()
-            .filter(|u| u.faction == Faction::Elf)
-            .all(|u| u.hp > 0)
-        {
-            Faction::Elf
-        } else {
-            Faction::Goblin
-        };
-        (winning_faction, outcome)
+            .map(|unit| if unit.hp > 0 { unit.hp as i32 } else { 0 })
+            .sum::<i32>();
+        let outcome = (rounds * hit_points_left).into();
+        let elves_killed = units
+            .iter()
+            .filter(|u| u.faction == Faction::Elf && u.hp <= 0)
+            .count();
+        // Elves come first in `units`.
+        let fallen_elf = units
+            .iter()
+            .position(|u| u.faction == Faction::Elf && u.hp <= 0)
+            .map(|elf_ref| self.elves[elf_ref]);
+        ctx.explain("full rounds", || rounds);
+        ctx.explain("hit points left", || hit_points_left);
+        ctx.explain("elves killed", || elves_killed);
+        ctx.explain("outcome", || &outcome);
+        (fallen_elf, outcome)
+    }
+
+    /// The attack power with which elves kill goblins with every blow, so
+    /// that more cannot help them, but at least one more than in part one.
+    fn strongest_attack_power(&self) -> i16 {
+        self.hit_points.max(4)
     }
 }
 
 impl Solver for PuzzleSolver {
-    fn presolve(&mut self, input: &str) {
+    fn presolve(&mut self, input: &str) -> Result<(), ParseError> {
+        const EXPECTED: &str = "a wall (#), an open cavern (.), a goblin (G) or an elf (E)";
+        input_lines(input, EXPECTED)?;
         for (y, line) in input.lines().enumerate() {
             for (x, ch) in line.chars().enumerate() {
                 match ch {
@@ -250,39 +336,91 @@ impl Solver for PuzzleSolver {
                         self.spaces.push((x, y));
                         self.elves.push((x, y));
                     }
-                    _ => {}
+                    '#' => {}
+                    _ => {
+                        return Err(ParseError::new(
+                            y + 1,
+                            x + 1,
+                            ch.to_string().as_str(),
+                            EXPECTED,
+                        ));
+                    }
                 }
             }
         }
+        // With few hit points or strong goblins, elves can lose even if they
+        // kill with every blow, and then part two has no answer.
+        let strongest = self.strongest_attack_power();
+        if let (Some((x, y)), _) = self.simulate_battle(strongest, &Context::default()) {
+            return Err(ParseError::new(
+                y + 1,
+                x + 1,
+                "E",
+                "an elf that survives when elves kill with every blow \
+                 (try more hit_points or less goblin_attack_power)",
+            ));
+        }
+        Ok(())
     }
 
-    fn solve_part_one(&mut self) -> String {
-        self.simulate_battle(3).1
+    fn solve_part_one(&mut self, ctx: &Context) -> Answer {
+        self.simulate_battle(3, ctx).1
     }
 
-    fn solve_part_two(&mut self) -> String {
+    fn solve_part_two(&mut self, ctx: &Context) -> Answer {
+        // The search starts above the attack power of part one, and ends at
+        // the strongest one, with which presolve made sure the elves win.
         let mut l = 3;
-        let mut r = 201;
+        let mut r = self.strongest_attack_power();
         let mut outcomes: HashMap<_, _> = HashMap::new();
+        let battle = |attack_power: i16| {
+            ctx.explain_section(&format!("attack power {attack_power}"), || {
+                self.simulate_battle(attack_power, ctx)
+            })
+        };
         while r > l + 1 {
-            let m = (r + l) / 2;
-            let (winning_faction, outcome) = self.simulate_battle(m);
+            let m = l + (r - l) / 2;
+            let (fallen_elf, outcome) = battle(m);
             outcomes.insert(m, outcome);
-            match winning_faction {
-                Faction::Elf => {
+            match fallen_elf {
+                None => {
                     r = m;
                 }
-                Faction::Goblin => {
+                Some(_) => {
                     l = m;
                 }
             }
         }
-        outcomes.remove(&r).unwrap()
+        // The search never fights with the strongest attack power itself.
+        outcomes.remove(&r).unwrap_or_else(|| battle(r).1)
+    }
+
+    fn params(&self) -> &'static [Param] {
+        PARAMS
+    }
+
+    fn set_param(&mut self, name: &str, value: i64) {
+        match name {
+            "goblin_attack_power" => self.goblin_attack_power = value as i16,
+            "hit_points" => self.hit_points = value as i16,
+            _ => unreachable!("unknown parameter {name}"),
+        }
     }
 }
 
 pub fn solver() -> PuzzleSolver {
-    Default::default()
+    with_default_params(PuzzleSolver::default())
+}
+
+inventory::submit! {
+    SolverInfo {
+        year: 2018,
+        day: 15,
+        implementation: REFERENCE,
+        title: "Beverage Bandits",
+        tags: &[Tag::Simulation, Tag::Grid, Tag::Search],
+        make_solver: || Box::new(solver()),
+    }
 }
 
 #[cfg(test)]
@@ -290,6 +428,8 @@ mod tests {
     use super::*;
     use test_log::test;
 
+    use crate::solver::set_params;
+
     #[test]
     fn provided_example_1() {
         let mut s = solver();
@@ -302,9 +442,13 @@ mod tests {
 #.....#
 #######
 ",
+        )
+        .unwrap();
+        assert_eq!(
+            Answer::Integer(27730),
+            s.solve_part_one(&Context::default())
         );
-        assert_eq!("27730", s.solve_part_one());
-        assert_eq!("4988", s.solve_part_two());
+        assert_eq!(Answer::Integer(4988), s.solve_part_two(&Context::default()));
     }
 
     #[test]
@@ -319,8 +463,12 @@ mod tests {
 #...E.#
 #######
 ",
+        )
+        .unwrap();
+        assert_eq!(
+            Answer::Integer(36334),
+            s.solve_part_one(&Context::default())
         );
-        assert_eq!("36334", s.solve_part_one());
     }
 
     #[test]
@@ -335,9 +483,16 @@ mod tests {
 #..E#.#
 #######
 ",
+        )
+        .unwrap();
+        assert_eq!(
+            Answer::Integer(39514),
+            s.solve_part_one(&Context::default())
+        );
+        assert_eq!(
+            Answer::Integer(31284),
+            s.solve_part_two(&Context::default())
         );
-        assert_eq!("39514", s.solve_part_one());
-        assert_eq!("31284", s.solve_part_two());
     }
 
     #[test]
@@ -352,9 +507,13 @@ mod tests {
 #...E.#
 #######
 ",
+        )
+        .unwrap();
+        assert_eq!(
+            Answer::Integer(27755),
+            s.solve_part_one(&Context::default())
         );
-        assert_eq!("27755", s.solve_part_one());
-        assert_eq!("3478", s.solve_part_two());
+        assert_eq!(Answer::Integer(3478), s.solve_part_two(&Context::default()));
     }
 
     #[test]
@@ -369,9 +528,13 @@ mod tests {
 #...#G#
 #######
 ",
+        )
+        .unwrap();
+        assert_eq!(
+            Answer::Integer(28944),
+            s.solve_part_one(&Context::default())
         );
-        assert_eq!("28944", s.solve_part_one());
-        assert_eq!("6474", s.solve_part_two());
+        assert_eq!(Answer::Integer(6474), s.solve_part_two(&Context::default()));
     }
 
     #[test]
@@ -388,8 +551,50 @@ mod tests {
 #.....G.#
 #########
 ",
+        )
+        .unwrap();
+        assert_eq!(
+            Answer::Integer(18740),
+            s.solve_part_one(&Context::default())
         );
-        assert_eq!("18740", s.solve_part_one());
-        assert_eq!("1140", s.solve_part_two());
+        assert_eq!(Answer::Integer(1140), s.solve_part_two(&Context::default()));
+    }
+
+    const FEW_HIT_POINTS: &str = "#######
+#.G...#
+#...EG#
+#.#.#G#
+#..G#E#
+#.....#
+#######
+";
+
+    #[test]
+    fn part_two_with_few_or_most_hit_points() {
+        for hit_points in [1, 2, 3, i16::MAX as i64] {
+            let mut s = solver();
+            let params = [
+                ("hit_points".into(), hit_points),
+                ("goblin_attack_power".into(), 0),
+            ];
+            set_params(&mut s, &params).unwrap();
+            s.presolve(FEW_HIT_POINTS).unwrap();
+            let ctx = Context::default();
+            assert_eq!(s.solve_part_two(&ctx), s.simulate_battle(4, &ctx).1);
+        }
+    }
+
+    #[test]
+    fn elves_that_cannot_win_are_rejected() {
+        for hit_points in [1, i16::MAX as i64] {
+            let mut s = solver();
+            let params = [
+                ("hit_points".into(), hit_points),
+                ("goblin_attack_power".into(), hit_points),
+            ];
+            set_params(&mut s, &params).unwrap();
+            let e = s.presolve(FEW_HIT_POINTS).unwrap_err();
+            assert_eq!((e.line, e.column, e.text.as_str()), (3, 5, "E"));
+        }
     }
 }
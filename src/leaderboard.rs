@@ -0,0 +1,124 @@
+//! Private leaderboards, as served by the Advent of Code website.
+
+use std::cmp::Reverse;
+use std::collections::BTreeMap;
+use std::collections::HashMap;
+
+use serde::Deserialize;
+use serde::Serialize;
+
+/// When a member earned a star.
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
+pub struct Star {
+    /// When the star was earned, in seconds since the Unix epoch.
+    pub get_star_ts: i64,
+    pub star_index: u64,
+}
+
+/// A member of a private leaderboard and their progress.
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
+pub struct Member {
+    pub id: u64,
+    /// Members who have not set a name are anonymous.
+    pub name: Option<String>,
+    pub stars: u32,
+    pub local_score: u64,
+    pub global_score: u64,
+    /// When the member last earned a star, in seconds since the Unix epoch,
+    /// or 0 if they have none.
+    pub last_star_ts: i64,
+    /// The stars earned, by day and then by part.
+    #[serde(rename = "completion_day_level")]
+    pub completion: BTreeMap<i8, BTreeMap<i8, Star>>,
+}
+
+impl Member {
+    /// Returns the name of the member as the website shows it.
+    pub fn display_name(&self) -> String {
+        match &self.name {
+            Some(name) => name.clone(),
+            None => format!("(anonymous user #{})", self.id),
+        }
+    }
+
+    /// Returns how many stars the member has for the given day.
+    pub fn stars_on(&self, day: i8) -> usize {
+        self.completion.get(&day).map_or(0, BTreeMap::len)
+    }
+}
+
+/// A private leaderboard for one event.
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
+pub struct Leaderboard {
+    pub event: String,
+    pub owner_id: u64,
+    /// The members, by id.
+    pub members: HashMap<String, Member>,
+}
+
+impl Leaderboard {
+    /// Returns the members ranked as on the website: by local score, then by
+    /// who got their last star first.
+    pub fn ranking(&self) -> Vec<&Member> {
+        let mut members: Vec<_> = self.members.values().collect();
+        members.sort_by_key(|member| {
+            (
+                Reverse(member.local_score),
+                Reverse(member.stars),
+                member.last_star_ts,
+                member.id,
+            )
+        });
+        members
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const LEADERBOARD: &str = r#"{
+        "event": "2018",
+        "owner_id": 1,
+        "members": {
+            "1": {
+                "id": 1, "name": "Owner", "stars": 3, "local_score": 7,
+                "global_score": 0, "last_star_ts": 1543700000,
+                "completion_day_level": {
+                    "1": {
+                        "1": {"get_star_ts": 1543640000, "star_index": 10},
+                        "2": {"get_star_ts": 1543650000, "star_index": 20}
+                    },
+                    "2": {"1": {"get_star_ts": 1543700000, "star_index": 30}}
+                }
+            },
+            "2": {
+                "id": 2, "name": null, "stars": 3, "local_score": 7,
+                "global_score": 0, "last_star_ts": 1543690000,
+                "completion_day_level": {
+                    "1": {
+                        "1": {"get_star_ts": 1543640001, "star_index": 11},
+                        "2": {"get_star_ts": 1543650001, "star_index": 21}
+                    },
+                    "2": {"1": {"get_star_ts": 1543690000, "star_index": 31}}
+                }
+            },
+            "3": {
+                "id": 3, "name": "Lurker", "stars": 0, "local_score": 0,
+                "global_score": 0, "last_star_ts": 0, "completion_day_level": {}
+            }
+        }
+    }"#;
+
+    #[test]
+    fn ranks_members() {
+        let leaderboard: Leaderboard = serde_json::from_str(LEADERBOARD).unwrap();
+        let ranking = leaderboard.ranking();
+        let names: Vec<_> = ranking.iter().map(|member| member.display_name()).collect();
+        assert_eq!(names, ["(anonymous user #2)", "Owner", "Lurker"]);
+        assert_eq!(ranking[1].stars_on(1), 2);
+        assert_eq!(ranking[1].stars_on(2), 1);
+        assert_eq!(ranking[1].completion[&1][&2].get_star_ts, 1543650000);
+        assert_eq!(ranking[2].stars_on(1), 0);
+    }
+}
@@ -0,0 +1,46 @@
+//! Solvers for Advent of Code 2018, and the plumbing to download puzzle
+//! inputs and submit answers.
+//!
+//! Every day registers a [`Solver`](solver::Solver) with the [`registry`],
+//! so a solver is found by year and day:
+//!
+//! ```
+//! use aoc18::context::Context;
+//! use aoc18::registry;
+//!
+//! let mut solver = registry::solver_for_day(2018, 1).unwrap();
+//! solver.presolve("+1\n-2\n+3\n+1\n").unwrap();
+//! assert_eq!(solver.solve_part_one(&Context::default()).to_string(), "3");
+//! ```
+//!
+//! [`AocClient`](aocclient::AocClient) talks to the Advent of Code website,
+//! and [`SubmissionLog`](autosubmit::SubmissionLog) keeps track of submitted
+//! answers so that the same wrong answer is never sent twice.
+
+pub mod aocclient;
+pub mod autosubmit;
+pub mod context;
+mod day01;
+mod day02;
+mod day03;
+mod day04;
+mod day05;
+mod day06;
+mod day07;
+mod day08;
+mod day09;
+mod day10;
+mod day11;
+mod day12;
+mod day13;
+mod day14;
+mod day15;
+pub mod leaderboard;
+pub mod parse;
+pub mod puzzle;
+pub mod registry;
+pub mod solver;
+#[cfg(test)]
+mod testing;
+pub mod trace;
+pub mod visualize;
@@ -1,66 +1,256 @@
-mod aocclient;
-mod autosubmit;
-mod day01;
-mod day02;
-mod day03;
-mod day04;
-mod day05;
-mod day06;
-mod day07;
-mod day08;
-mod day09;
-mod day10;
-mod day11;
-mod day12;
-mod day13;
-mod day14;
-mod day15;
-mod solver;
+mod bench;
+mod config;
+mod report;
+mod runner;
+mod server;
+mod watchdog;
 
 use std::{
-    env,
+    fs,
+    io::{self, IsTerminal, Read},
+    path::PathBuf,
+    process::{self, ExitCode},
     time::{Duration, Instant},
 };
 
-use autosubmit::next_unsolved_day;
-use clap::Parser;
+use aoc18::autosubmit::{self, SubmissionLog};
+use aoc18::registry::{self, SolverInfo};
+use aoc18::solver::{Answer, set_params};
+use aoc18::visualize::{Visualization, VisualizationFormat};
+use aoc18::{aocclient, context};
+use clap::{Parser, Subcommand};
+use config::Config;
 
-use solver::Solver;
+use report::{
+    CrossCheckResult, DayReport, DayStatus, OutputFormat, PartOutcome, PartReport,
+    SubmissionReport, Verdict, VerificationResult,
+};
+use runner::PartRunner;
+use server::SolveService;
+
+const DEFAULT_YEAR: i16 = 2018;
+const DEFAULT_INPUT_CACHE_DIR: &str = "inputs";
 
 #[derive(Parser)]
 #[command(version, about, long_about = None)]
 struct Args {
-    #[arg(short, long)]
-    submit: bool,
-
-    #[arg(short, long)]
+    #[arg(short, long, global = true, env = "AOC_COOKIE", hide_env_values = true)]
     cookie: Option<String>,
 
+    /// The Advent of Code event to work on [default: 2018]
+    #[arg(short, long, global = true, env = "AOC_YEAR")]
+    year: Option<i16>,
+
+    /// Where to keep the log of submitted answers [default: results.toml]
+    #[arg(long, global = true, env = "AOC_RESULTS")]
+    results: Option<PathBuf>,
+
+    /// Where to keep downloaded puzzle inputs, so that they are only
+    /// downloaded once [default: inputs]
+    #[arg(long, global = true, env = "AOC_INPUT_CACHE_DIR")]
+    input_cache_dir: Option<PathBuf>,
+
+    /// Download puzzle inputs again even if they have been downloaded before.
+    #[arg(long, global = true)]
+    refresh: bool,
+
+    #[arg(long, global = true, env = "AOC_BASE_URL")]
+    base_url: Option<String>,
+
+    /// How to print the results. Logs always go to stderr.
+    #[arg(short, long, global = true, value_enum, default_value_t = OutputFormat::Text)]
+    format: OutputFormat,
+
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Download the puzzle input and print it to stdout.
+    Fetch {
+        #[arg(short, long)]
+        day: Option<i8>,
+    },
+
+    /// Show the puzzle description as Markdown. It is kept next to the
+    /// puzzle input, so it can be read offline once downloaded.
+    Read {
+        #[arg(short, long)]
+        day: Option<i8>,
+    },
+
+    /// Solve a puzzle without submitting the answers.
+    Solve(SolveArgs),
+
+    /// Submit an answer, either given on the command line or computed by the solver.
+    Submit {
+        #[arg(short, long)]
+        day: Option<i8>,
+
+        /// Which part the answer is for. Required when the answer is given.
+        #[arg(short, long, requires = "answer", value_parser = clap::value_parser!(i8).range(1..=2))]
+        part: Option<i8>,
+
+        #[arg(long, conflicts_with = "answer")]
+        part_two_only: bool,
+
+        /// The answer to submit. If omitted, the solver computes it.
+        #[arg(requires = "part")]
+        answer: Option<String>,
+    },
+
+    /// Serve the solvers over HTTP: POST a puzzle input to /solve/{day} to
+    /// solve it, or GET /days to list the solvers.
+    Serve {
+        /// The address to listen on.
+        #[arg(short, long, default_value = "127.0.0.1:8018")]
+        address: String,
+
+        /// Give up on a part if it takes longer than this many seconds, so
+        /// that a runaway request does not tie up a worker forever.
+        #[arg(short, long, default_value = "60", value_parser = parse_seconds)]
+        timeout: Duration,
+
+        /// Answer up to this many requests concurrently.
+        #[arg(short, long, default_value_t = 4, value_parser = clap::value_parser!(u16).range(1..))]
+        jobs: u16,
+    },
+
+    /// Show the standings on a private leaderboard.
+    Leaderboard {
+        /// The id of the leaderboard, which is the number at the end of its
+        /// URL.
+        #[arg(long, env = "AOC_LEADERBOARD")]
+        id: u64,
+    },
+
+    /// Show the stars earned for each day, according to the submission log.
+    Status,
+
+    /// Show the submission log for a puzzle.
+    History {
+        #[arg(short, long)]
+        day: i8,
+
+        /// Only show this part; both parts are shown by default.
+        #[arg(short, long, value_parser = clap::value_parser!(i8).range(1..=2))]
+        part: Option<i8>,
+    },
+}
+
+#[derive(clap::Args)]
+struct SolveArgs {
     #[arg(short, long)]
     part_two_only: bool,
 
     #[arg(short, long)]
     day: Option<i8>,
+
+    /// Solve every implemented day and print a summary table.
+    #[arg(short, long, conflicts_with = "day")]
+    all: bool,
+
+    /// Read the puzzle input from a local file ("-" for stdin) instead of
+    /// downloading it.
+    #[arg(short, long, conflicts_with = "all")]
+    input: Option<String>,
+
+    /// Benchmark presolve and both parts over this many runs, each on a fresh
+    /// solver, instead of solving once.
+    #[arg(short, long, conflicts_with = "all", value_parser = clap::value_parser!(u32).range(1..))]
+    bench: Option<u32>,
+
+    /// Give up on a part if it takes longer than this many seconds.
+    #[arg(short, long, conflicts_with = "bench", value_parser = parse_seconds)]
+    timeout: Option<Duration>,
+
+    /// Solve up to this many days concurrently in run-all and verify modes.
+    #[arg(short, long, default_value_t = 1, value_parser = clap::value_parser!(u16).range(1..))]
+    jobs: u16,
+
+    /// Re-solve every day with an accepted answer in the submission log, and
+    /// fail if any answer has changed.
+    #[arg(long, conflicts_with_all = ["day", "all", "input", "bench", "part_two_only"])]
+    verify: bool,
+
+    /// Override a solver parameter, given as NAME=VALUE. Use --list to see the
+    /// parameters of each day.
+    #[arg(short = 'P', long = "param", value_name = "NAME=VALUE", value_parser = parse_param, conflicts_with_all = ["all", "verify"])]
+    params: Vec<(String, i64)>,
+
+    /// List the implemented days instead of solving anything.
+    #[arg(short, long, conflicts_with_all = ["day", "all", "input", "bench", "part_two_only", "verify"])]
+    list: bool,
+
+    /// Record the simulation of days that can be visualized (10, 12, 13 and
+    /// 15) and replay it in the terminal or write it as images.
+    #[arg(long, value_name = "FORMAT", conflicts_with_all = ["all", "verify", "bench", "list"])]
+    visualize: Option<VisualizationFormat>,
+
+    /// The directory to write PPM and GIF visualizations to.
+    #[arg(long, value_name = "DIR", default_value = ".", requires = "visualize")]
+    frames_dir: PathBuf,
+
+    /// Stop recording a part after this many frames.
+    #[arg(long, default_value_t = 500, requires = "visualize", value_parser = clap::value_parser!(u32).range(1..))]
+    max_frames: u32,
+
+    /// The time between frames of a visualization, in seconds.
+    #[arg(long, default_value = "0.1", requires = "visualize", value_parser = parse_seconds)]
+    frame_delay: Duration,
+
+    /// Run this implementation of the day instead of the reference one. Use
+    /// --list to see the implementations of each day.
+    #[arg(long = "impl", value_name = "NAME", default_value = registry::REFERENCE, conflicts_with_all = ["all", "verify", "list"])]
+    implementation: String,
+
+    /// Run every implementation of the day, and fail if their answers differ.
+    #[arg(long, conflicts_with_all = ["all", "verify", "list", "bench", "implementation", "visualize", "explain"])]
+    cross_check: bool,
+
+    /// Check the solver against the examples in the puzzle description
+    /// before solving the puzzle input.
+    #[arg(long, conflicts_with_all = ["all", "verify", "list", "bench", "cross_check"])]
+    examples: bool,
+
+    /// Show the intermediate results that led to each answer.
+    #[arg(short, long, conflicts_with_all = ["all", "verify", "bench", "list"])]
+    explain: bool,
 }
 
-fn solver_for_day(day: i8) -> Option<Box<dyn Solver>> {
-    match day {
-        1 => Some(Box::new(day01::solver())),
-        2 => Some(Box::new(day02::solver())),
-        3 => Some(Box::new(day03::solver())),
-        4 => Some(Box::new(day04::solver())),
-        5 => Some(Box::new(day05::solver())),
-        6 => Some(Box::new(day06::solver())),
-        7 => Some(Box::new(day07::solver())),
-        8 => Some(Box::new(day08::solver())),
-        9 => Some(Box::new(day09::solver())),
-        10 => Some(Box::new(day10::solver())),
-        11 => Some(Box::new(day11::solver())),
-        12 => Some(Box::new(day12::solver())),
-        13 => Some(Box::new(day13::solver())),
-        14 => Some(Box::new(day14::solver())),
-        15 => Some(Box::new(day15::solver())),
-        _ => None,
+impl SolveArgs {
+    fn visualization(&self) -> Option<Visualization> {
+        Some(Visualization {
+            format: self.visualize?,
+            output_dir: self.frames_dir.clone(),
+            max_frames: self.max_frames as usize,
+            frame_delay: self.frame_delay,
+        })
+    }
+}
+
+fn parse_seconds(s: &str) -> Result<Duration, String> {
+    let seconds: f64 = s.parse().map_err(|e| format!("{e}"))?;
+    Duration::try_from_secs_f64(seconds).map_err(|e| format!("{e}"))
+}
+
+fn parse_param(s: &str) -> Result<(String, i64), String> {
+    let (name, value) = s
+        .split_once('=')
+        .ok_or_else(|| format!("expected NAME=VALUE, got {s}"))?;
+    let value = value.parse().map_err(|e| format!("{e}"))?;
+    Ok((name.to_string(), value))
+}
+
+/// Reads the puzzle input from the file at `path`, or from stdin if `path` is "-".
+fn read_input(path: &str) -> io::Result<String> {
+    if path == "-" {
+        let mut input = String::new();
+        io::stdin().read_to_string(&mut input)?;
+        Ok(input)
+    } else {
+        fs::read_to_string(path)
     }
 }
 
@@ -70,66 +260,745 @@ fn timeit<T, F: FnOnce() -> T>(f: F) -> (T, Duration) {
     (result, start.elapsed())
 }
 
-fn main() {
-    pretty_env_logger::init();
-    log::info!("Advent of Code 2018 Solver");
+/// Settings and services shared by all subcommands.
+struct App {
+    client: aocclient::AocClient,
+    submission_log: SubmissionLog,
+    year: i16,
+    format: OutputFormat,
+}
 
-    let args = Args::parse();
+/// Solves one day of run-all mode. Nothing is logged while a part runs, so
+/// that concurrently running days do not skew each other's timings.
+fn solve_day(
+    year: i16,
+    day: i8,
+    input: &Result<String, String>,
+    timeout: Option<Duration>,
+    show_progress: bool,
+) -> DayReport {
+    let failed = |outcome: PartOutcome| DayReport {
+        year,
+        day,
+        parts: vec![
+            PartReport {
+                part: 1,
+                outcome: outcome.clone(),
+                submission: None,
+            },
+            PartReport {
+                part: 2,
+                outcome,
+                submission: None,
+            },
+        ],
+    };
+    let input = match input {
+        Ok(input) => input,
+        Err(error) => {
+            return failed(PartOutcome::Failed {
+                error: error.clone(),
+            });
+        }
+    };
+    let mut runner = PartRunner::new(year, day, input.as_str(), timeout);
+    if show_progress {
+        runner = runner.with_progress();
+    }
+    if let Err(outcome) = runner.presolve() {
+        return failed(outcome);
+    }
+    log::info!("solving day {day}...");
+    let parts = [1, 2]
+        .map(|part| PartReport {
+            part,
+            outcome: runner.run(part),
+            submission: None,
+        })
+        .into();
+    log::info!("finished day {day}");
+    DayReport { year, day, parts }
+}
 
-    let client = aocclient::AocClient::new().expect("creating AoC client");
+/// Solves the given days, running up to `jobs` of them concurrently.
+fn solve_days(app: &App, days: &[i8], timeout: Option<Duration>, jobs: usize) -> Vec<DayReport> {
+    let year = app.year;
+    // Inputs are downloaded up front, one at a time, to be polite to the server.
+    let inputs: Vec<_> = days
+        .iter()
+        .map(|&day| {
+            log::info!("retrieving puzzle input for day {day}...");
+            let input = app
+                .client
+                .get_puzzle_input(year, day)
+                .map_err(|e| format!("error retrieving puzzle input: {e}"));
+            (day, input)
+        })
+        .collect();
+    runner::parallel_map(jobs, &inputs, |(day, input)| {
+        // Progress lines of concurrently running days would overwrite each other.
+        solve_day(year, *day, input, timeout, jobs == 1)
+    })
+}
 
-    // Get the AoC cookie, either from the command line, or from the env variable.
-    if let Some(cookie) = args.cookie {
-        client.set_cookie(cookie.as_str());
-    } else if let Ok(cookie) = env::var("AOC_COOKIE") {
-        client.set_cookie(&cookie);
-    } else {
-        log::warn!("you must specify the session cookie with --cookie or AOC_COOKIE env variable");
-        return;
+/// Solves every day, and returns whether all parts were solved.
+fn solve_all(app: &App, timeout: Option<Duration>, jobs: usize) -> bool {
+    let days: Vec<_> = registry::solvers(app.year)
+        .iter()
+        .map(|info| info.day)
+        .collect();
+    let reports = solve_days(app, &days, timeout, jobs);
+    report::print_summary(&reports, app.format);
+    reports.iter().all(DayReport::is_solved)
+}
+
+/// Returns the days that have a solver and an accepted answer in the
+/// submission log, which are the days that `--verify` re-solves.
+fn days_to_verify(submission_log: &SubmissionLog, year: i16) -> Vec<i8> {
+    let has_accepted_answer = |day: i8, part: i8| {
+        submission_log
+            .puzzle_log_entry(year, day, part)
+            .is_some_and(|entry| entry.accepted_answer.is_some())
+    };
+    registry::solvers(year)
+        .iter()
+        .map(|info| info.day)
+        .filter(|day| has_accepted_answer(*day, 1) || has_accepted_answer(*day, 2))
+        .collect()
+}
+
+/// Re-solves every day that has an accepted answer in the submission log and
+/// compares the results. Returns whether all answers still match.
+fn verify(app: &App, timeout: Option<Duration>, jobs: usize) -> bool {
+    let year = app.year;
+    let accepted_answer = |day: i8, part: i8| {
+        app.submission_log
+            .puzzle_log_entry(year, day, part)
+            .and_then(|entry| entry.accepted_answer)
+    };
+    let days = days_to_verify(&app.submission_log, year);
+    let results: Vec<_> = solve_days(app, &days, timeout, jobs)
+        .into_iter()
+        .flat_map(|report| {
+            let day = report.day;
+            report.parts.into_iter().filter_map(move |part| {
+                let expected = accepted_answer(day, part.part)?;
+                Some(VerificationResult::new(
+                    year,
+                    day,
+                    part.part,
+                    expected,
+                    part.outcome,
+                ))
+            })
+        })
+        .collect();
+    report::print_verification(&results, app.format);
+    results
+        .iter()
+        .all(|result| result.result != Verdict::Failed)
+}
+
+/// Whether an answer was submitted and accepted.
+fn is_accepted(submission: &Option<SubmissionReport>) -> bool {
+    submission
+        .as_ref()
+        .is_some_and(|submission| submission.result == aocclient::ValidationResult::Accepted)
+}
+
+/// Submits the answer through the submission log, keeping track of how long
+/// the server made us wait. Returns `None` if the answer could not be
+/// submitted, which is logged.
+fn submit(app: &App, day: i8, level: i8, answer: &Answer) -> Option<SubmissionReport> {
+    let mut throttled = Duration::ZERO;
+    let result =
+        app.submission_log
+            .try_submit_with_cache(app.year, day, level, answer, |y, d, l, a| {
+                let result = app.client.submit_answer(y, d, l, a)?;
+                if let aocclient::ValidationResult::Throttled(timeout) = result {
+                    throttled += timeout;
+                }
+                Ok::<_, aocclient::Error>(result)
+            });
+    match result {
+        Ok(result) => Some(SubmissionReport { result, throttled }),
+        Err(e) => {
+            log::error!("error submitting answer: {e}");
+            None
+        }
     }
-    let day = if let Some(day) = args.day {
-        day
-    } else {
-        next_unsolved_day()
+}
+
+/// Solves both parts (or only part two) of one day's puzzle, optionally
+/// submitting the answers, and prints the results. Returns whether every part
+/// was solved, and its answer accepted if submitted.
+fn solve_and_report(
+    app: &App,
+    day: i8,
+    mut runner: PartRunner,
+    part_two_only: bool,
+    submit_answers: bool,
+) -> bool {
+    let mut parts = vec![];
+    let first_part = if part_two_only { 2 } else { 1 };
+    if let Err(outcome) = runner.presolve() {
+        match &outcome {
+            PartOutcome::Failed { error } => log::error!("{error}"),
+            _ => log::error!("presolve failed: {}", outcome.answer()),
+        }
+        for part in first_part..=2 {
+            parts.push(PartReport {
+                part,
+                outcome: outcome.clone(),
+                submission: None,
+            });
+        }
+        let year = app.year;
+        report::print_day(&DayReport { year, day, parts }, app.format);
+        return false;
+    }
+    let mut all_accepted = true;
+    for part in first_part..=2 {
+        let part_name = if part == 1 { "part one" } else { "part two" };
+        log::info!("solving {part_name}...");
+        let outcome = runner.run(part);
+        let submission = match &outcome {
+            PartOutcome::Solved {
+                answer,
+                duration,
+                trace,
+            } => {
+                match answer {
+                    Answer::Art(art) => {
+                        log::info!("{part_name} solved in {duration:?}, answer:\n{art}")
+                    }
+                    _ => log::info!("{part_name} solved in {duration:?}, answer: {answer}"),
+                }
+                if !trace.is_empty() {
+                    report::print_trace(part, trace, app.format);
+                }
+                if submit_answers {
+                    log::info!("submitting {part_name}...");
+                    let submission = submit(app, day, part, answer);
+                    if let Some(submission) = &submission {
+                        log::info!("{part_name} submission result: {:?}", submission.result);
+                    }
+                    all_accepted &= is_accepted(&submission);
+                    submission
+                } else {
+                    None
+                }
+            }
+            PartOutcome::TimedOut { timeout } => {
+                log::error!("{part_name} timed out after {timeout:?}");
+                None
+            }
+            PartOutcome::Provided { .. } | PartOutcome::Failed { .. } => {
+                log::error!("{part_name} failed: {}", outcome.answer());
+                None
+            }
+        };
+        parts.push(PartReport {
+            part,
+            outcome,
+            submission,
+        });
+    }
+    let report = DayReport {
+        year: app.year,
+        day,
+        parts,
     };
-    let solver = solver_for_day(day);
-    if solver.is_none() {
-        log::error!("this solver cannot solve day {}", day);
-        return;
+    report::print_day(&report, app.format);
+    report.is_solved() && all_accepted
+}
+
+/// Returns the named implementation for the given day, logging an error if
+/// there is none.
+fn require_solver(year: i16, day: i8, implementation: &str) -> Option<&'static SolverInfo> {
+    let implementations = registry::implementations(year, day);
+    if implementations.is_empty() {
+        log::error!("this solver cannot solve day {} of {}", day, year);
+        return None;
     }
-    let mut solver = solver.unwrap();
-    let solver = solver.as_mut();
-    log::info!("solving Advent of Code day {}", day);
+    let info = implementations
+        .iter()
+        .find(|info| info.implementation == implementation);
+    if info.is_none() {
+        log::error!(
+            "day {day} has no implementation {implementation}: expected one of {}",
+            implementations
+                .iter()
+                .map(|info| info.implementation)
+                .collect::<Vec<_>>()
+                .join(", ")
+        );
+    }
+    info.copied()
+}
+
+/// Reads the puzzle input from the file at `path` if given, or downloads it.
+fn puzzle_input(app: &App, day: i8, path: Option<&str>) -> Option<String> {
+    let Some(path) = path else {
+        return download_input(app, day);
+    };
+    log::info!("reading puzzle input from {path}...");
+    match read_input(path) {
+        Ok(input) => Some(input),
+        Err(e) => {
+            log::error!("error reading puzzle input: {e}");
+            None
+        }
+    }
+}
+
+fn download_input(app: &App, day: i8) -> Option<String> {
     log::info!("retrieving puzzle input...");
-    match client.get_puzzle_input(day) {
-        Ok(input) => {
-            solver.presolve(input.as_str());
-            if !args.part_two_only {
-                log::info!("solving part one...");
-                let (answer, part_one_time) = timeit(|| solver.solve_part_one());
-                log::info!("part one solved in {part_one_time:?}, answer: {answer}");
-                if args.submit {
-                    log::info!("submitting part one...");
-                    let result =
-                        autosubmit::submit_with_cache(day, 1, answer.as_str(), |d, l, a| {
-                            client.submit_answer(d, l, a).unwrap()
-                        });
-                    log::info!("part one submission result: {result:?}");
-                }
+    match app.client.get_puzzle_input(app.year, day) {
+        Ok(input) => Some(input),
+        Err(e) => {
+            log::error!("error retrieving puzzle input: {e}");
+            None
+        }
+    }
+}
+
+fn fetch(app: &App, day: i8) -> bool {
+    let Some(input) = download_input(app, day) else {
+        return false;
+    };
+    print!("{input}");
+    true
+}
+
+fn read(app: &App, day: i8) -> bool {
+    log::info!("retrieving puzzle description...");
+    match app.client.get_puzzle_description(app.year, day) {
+        Ok(description) => {
+            print!("{description}");
+            true
+        }
+        Err(e) => {
+            log::error!("error retrieving puzzle description: {e}");
+            false
+        }
+    }
+}
+
+/// Runs the `solve` command. Returns whether every part that was asked for
+/// was solved, so that a failing or timed out part fails the command.
+fn solve(app: &App, args: SolveArgs) -> bool {
+    if args.list {
+        report::print_solver_list(&registry::all_implementations(app.year), app.format);
+        return true;
+    }
+    if args.all {
+        return solve_all(app, args.timeout, args.jobs.into());
+    }
+    let year = app.year;
+    let day = args
+        .day
+        .unwrap_or_else(|| app.submission_log.next_unsolved_day(year));
+    let Some(info) = require_solver(year, day, &args.implementation) else {
+        return false;
+    };
+    if let Err(e) = set_params((info.make_solver)().as_mut(), &args.params) {
+        log::error!("{e}");
+        return false;
+    }
+    log::info!("solving Advent of Code day {}", day);
+    let Some(input) = puzzle_input(app, day, args.input.as_deref()) else {
+        return false;
+    };
+    if let Some(iterations) = args.bench {
+        log::info!("benchmarking over {iterations} runs...");
+        return match bench::benchmark(
+            || {
+                let mut solver = (info.make_solver)();
+                set_params(solver.as_mut(), &args.params).unwrap();
+                solver
+            },
+            input.as_str(),
+            iterations as usize,
+        ) {
+            Ok(report) => {
+                report::print_benchmark_report(year, day, &report, app.format);
+                report.is_complete()
+            }
+            Err(e) => {
+                log::error!("cannot parse puzzle input: {}", e.with_day(day));
+                false
             }
-            log::info!("solving part two...");
-            let (answer, part_two_time) = timeit(|| solver.solve_part_two());
-            log::info!("part two solved in {part_two_time:?}, answer: {answer}");
-            if args.submit {
-                log::info!("submitting part two...");
-                let result = autosubmit::submit_with_cache(day, 2, answer.as_str(), |d, l, a| {
-                    client.submit_answer(d, l, a).unwrap()
-                });
-                log::info!("part one submission result: {result:?}");
+        };
+    }
+    // A failure to retrieve the examples fails the command, but only after
+    // solving the puzzle input.
+    let examples_retrieved = !args.examples || check_examples(app, day, &args);
+    let mut runner = PartRunner::new(year, day, input.as_str(), args.timeout)
+        .with_implementation(&args.implementation)
+        .with_params(&args.params)
+        .with_visualization(args.visualization());
+    if args.explain {
+        runner = runner.with_explain();
+    }
+    // The progress line would get in the way of a replay in the terminal.
+    if args.visualize != Some(VisualizationFormat::Terminal) {
+        runner = runner.with_progress();
+    }
+    solve_and_report(app, day, runner, args.part_two_only, false) && examples_retrieved
+}
+
+/// Solves the examples in the puzzle description of the day, and logs which
+/// answers differ from those in the description. Examples often need other
+/// solver parameters than the puzzle input, so this does not stop anything.
+/// Returns whether the examples could be retrieved.
+fn check_examples(app: &App, day: i8, args: &SolveArgs) -> bool {
+    log::info!("retrieving examples...");
+    let examples = match app.client.get_puzzle_examples(app.year, day) {
+        Ok(examples) => examples,
+        Err(e) => {
+            log::error!("error retrieving examples: {e}");
+            return false;
+        }
+    };
+    if examples.is_empty() {
+        log::warn!("found no examples in the description of day {day}");
+    }
+    for example in examples {
+        let part = example.part;
+        if args.part_two_only && part == 1 {
+            continue;
+        }
+        let mut runner = PartRunner::new(app.year, day, &example.input, args.timeout)
+            .with_implementation(&args.implementation)
+            .with_params(&args.params);
+        match runner.run(part) {
+            PartOutcome::Solved { answer, .. } if answer.to_string() == example.answer => {
+                log::info!("example of part {part} passed: {answer}")
             }
+            PartOutcome::Solved { answer, .. } => log::warn!(
+                "example of part {part} failed: expected {}, got {answer}",
+                example.answer
+            ),
+            outcome => log::warn!("example of part {part} failed: {}", outcome.answer()),
+        }
+    }
+    true
+}
+
+/// Solves one day with every implementation and compares their answers to
+/// those of the reference implementation. Returns whether they all agree.
+fn cross_check(app: &App, args: SolveArgs) -> bool {
+    let year = app.year;
+    let day = args
+        .day
+        .unwrap_or_else(|| app.submission_log.next_unsolved_day(year));
+    let Some(reference) = require_solver(year, day, registry::REFERENCE) else {
+        return false;
+    };
+    if let Err(e) = set_params((reference.make_solver)().as_mut(), &args.params) {
+        log::error!("{e}");
+        return false;
+    }
+    log::info!("cross-checking Advent of Code day {}", day);
+    let Some(input) = puzzle_input(app, day, args.input.as_deref()) else {
+        return false;
+    };
+    let first_part = if args.part_two_only { 2 } else { 1 };
+    let mut results: Vec<CrossCheckResult> = vec![];
+    for info in registry::implementations(year, day) {
+        if let Err(e) = set_params((info.make_solver)().as_mut(), &args.params) {
+            log::error!("implementation {}: {e}", info.implementation);
+            return false;
+        }
+        log::info!("solving with the {} implementation...", info.implementation);
+        let mut runner = PartRunner::new(year, day, input.as_str(), args.timeout)
+            .with_implementation(info.implementation)
+            .with_params(&args.params)
+            .with_progress();
+        let presolved = runner.presolve();
+        for part in first_part..=2 {
+            let outcome = match &presolved {
+                Ok(()) => runner.run(part),
+                Err(outcome) => outcome.clone(),
+            };
+            let reference_answer = results
+                .iter()
+                .find(|result| result.part == part && result.implementation == registry::REFERENCE)
+                .map_or(&outcome, |result| &result.outcome);
+            let agrees = matches!(
+                (&outcome, reference_answer),
+                (PartOutcome::Solved { answer, .. }, PartOutcome::Solved { answer: expected, .. })
+                    if answer == expected
+            );
+            results.push(CrossCheckResult {
+                year,
+                day,
+                part,
+                implementation: info.implementation,
+                outcome,
+                agrees,
+            });
+        }
+    }
+    report::print_cross_check(&results, app.format);
+    results.iter().all(|result| result.agrees)
+}
+
+fn submit_command(
+    app: &App,
+    day: i8,
+    part: Option<i8>,
+    part_two_only: bool,
+    answer: Option<String>,
+) -> bool {
+    if let (Some(part), Some(answer)) = (part, answer) {
+        log::info!("submitting answer for day {day} part {part}...");
+        let answer = Answer::parse(answer.as_str());
+        let submission = submit(app, day, part, &answer);
+        if let Some(submission) = &submission {
+            log::info!("submission result: {:?}", submission.result);
+        }
+        let accepted = is_accepted(&submission);
+        let parts = vec![PartReport {
+            part,
+            outcome: PartOutcome::Provided { answer },
+            submission,
+        }];
+        let year = app.year;
+        report::print_day(&DayReport { year, day, parts }, app.format);
+        return accepted;
+    }
+    if require_solver(app.year, day, registry::REFERENCE).is_none() {
+        return false;
+    }
+    log::info!("solving Advent of Code day {}", day);
+    let Some(input) = download_input(app, day) else {
+        return false;
+    };
+    let runner = PartRunner::new(app.year, day, input.as_str(), None).with_progress();
+    solve_and_report(app, day, runner, part_two_only, true)
+}
+
+fn serve(app: &App, address: &str, timeout: Duration, jobs: usize) -> bool {
+    let server = match tiny_http::Server::http(address) {
+        Ok(server) => server,
+        Err(e) => {
+            log::error!("cannot listen on {address}: {e}");
+            return false;
+        }
+    };
+    log::info!("serving the solvers on http://{}", server.server_addr());
+    let service = SolveService {
+        year: app.year,
+        timeout: Some(timeout),
+    };
+    service.serve(&server, jobs);
+    true
+}
+
+fn leaderboard(app: &App, id: u64) -> bool {
+    log::info!("retrieving private leaderboard {id}...");
+    match app.client.get_private_leaderboard(app.year, id) {
+        Ok(leaderboard) => {
+            report::print_leaderboard(&leaderboard, app.format);
+            true
+        }
+        Err(e) => {
+            log::error!("error retrieving leaderboard: {e}");
+            false
+        }
+    }
+}
+
+fn status(app: &App) {
+    let year = app.year;
+    let days: Vec<_> = (1..=25)
+        .map(|day| DayStatus {
+            year,
+            day,
+            part_one: app.submission_log.puzzle_log_entry(year, day, 1),
+            part_two: app.submission_log.puzzle_log_entry(year, day, 2),
+        })
+        .filter(|status| {
+            registry::find(year, status.day).is_some()
+                || status.part_one.is_some()
+                || status.part_two.is_some()
+        })
+        .collect();
+    report::print_status(&days, app.format);
+}
+
+fn history(app: &App, day: i8, part: Option<i8>) {
+    let entries: Vec<_> = [1, 2]
+        .into_iter()
+        .filter(|p| part.is_none_or(|part| part == *p))
+        .map(|p| {
+            let entry = app.submission_log.puzzle_log_entry(app.year, day, p);
+            (p, entry.unwrap_or_default())
+        })
+        .collect();
+    report::print_history(app.year, day, &entries, app.format);
+}
+
+/// Whether solving has to download a puzzle input, rather than read it from
+/// a file or the input cache.
+fn solve_downloads_input(
+    client: &aocclient::AocClient,
+    submission_log: &SubmissionLog,
+    year: i16,
+    args: &SolveArgs,
+) -> bool {
+    if args.input.is_some() || args.list {
+        return false;
+    }
+    let days: Vec<_> = if args.verify {
+        days_to_verify(submission_log, year)
+    } else if args.all {
+        registry::solvers(year)
+            .iter()
+            .map(|info| info.day)
+            .collect()
+    } else {
+        vec![
+            args.day
+                .unwrap_or_else(|| submission_log.next_unsolved_day(year)),
+        ]
+    };
+    days.into_iter()
+        .any(|day| !client.has_cached_input(year, day))
+}
+
+fn main() -> ExitCode {
+    pretty_env_logger::init();
+    let handler = ctrlc::set_handler(|| {
+        if context::interrupt() {
+            // Solvers that never report progress cannot be cancelled.
+            process::exit(130);
+        }
+        if io::stderr().is_terminal() {
+            // Start a new line rather than append to a progress line.
+            eprintln!();
         }
+        log::warn!("interrupted, stopping solvers (press Ctrl-C again to quit)");
+    });
+    if let Err(e) = handler {
+        log::warn!("cannot handle Ctrl-C: {e}");
+    }
+    let args = Args::parse();
+
+    // Settings come from the command line first, then the environment (both
+    // handled by clap), then the config file.
+    let config = match Config::load() {
+        Ok(config) => config,
         Err(e) => {
-            log::error!("error retrieving puzzle input: {e:#?}");
+            log::error!("{e}");
+            return ExitCode::FAILURE;
         }
+    };
+    let mut client = match args.base_url.or(config.base_url.clone()) {
+        Some(base_url) => aocclient::AocClient::new_with_base(base_url.as_str()),
+        None => aocclient::AocClient::new(),
+    }
+    .expect("creating AoC client")
+    .with_input_cache(
+        args.input_cache_dir
+            .or(config.input_cache_dir.clone())
+            .unwrap_or(DEFAULT_INPUT_CACHE_DIR.into()),
+    );
+    if args.refresh {
+        client = client.with_refresh();
+    }
+
+    let year = args.year.or(config.year).unwrap_or(DEFAULT_YEAR);
+    log::info!("Advent of Code {year} Solver");
+    let submission_log = SubmissionLog::new(
+        args.results
+            .or(config.results_log.clone())
+            .unwrap_or(autosubmit::DEFAULT_FILE.into()),
+    );
+
+    // Only the commands that talk to the server need the cookie.
+    let needs_cookie = match &args.command {
+        Command::Fetch { day } => {
+            let day = day.unwrap_or_else(|| submission_log.next_unsolved_day(year));
+            !client.has_cached_input(year, day)
+        }
+        Command::Submit { .. } | Command::Leaderboard { .. } => true,
+        Command::Solve(solve_args) => {
+            solve_downloads_input(&client, &submission_log, year, solve_args)
+        }
+        // Part one can be read without logging in.
+        Command::Read { .. }
+        | Command::Serve { .. }
+        | Command::Status
+        | Command::History { .. } => false,
+    };
+    let cookie = match args.cookie.map_or_else(|| config.cookie(), |c| Ok(Some(c))) {
+        Ok(cookie) => cookie,
+        Err(e) if needs_cookie => {
+            log::error!("{e}");
+            return ExitCode::FAILURE;
+        }
+        Err(e) => {
+            log::warn!("{e}");
+            None
+        }
+    };
+    if let Some(cookie) = cookie {
+        client.set_cookie(cookie.as_str());
+    } else if needs_cookie {
+        log::warn!(
+            "you must specify the session cookie with --cookie, AOC_COOKIE env variable or {}",
+            "session_cookie in aoc.toml"
+        );
+        return ExitCode::FAILURE;
+    }
+    let app = App {
+        client,
+        submission_log,
+        year,
+        format: args.format,
+    };
+    let next_unsolved_day = || app.submission_log.next_unsolved_day(app.year);
+    let succeeded = match args.command {
+        Command::Fetch { day } => fetch(&app, day.unwrap_or_else(next_unsolved_day)),
+        Command::Read { day } => read(&app, day.unwrap_or_else(next_unsolved_day)),
+        Command::Solve(solve_args) if solve_args.verify => {
+            verify(&app, solve_args.timeout, solve_args.jobs.into())
+        }
+        Command::Solve(solve_args) if solve_args.cross_check => cross_check(&app, solve_args),
+        Command::Solve(solve_args) => solve(&app, solve_args),
+        Command::Submit {
+            day,
+            part,
+            part_two_only,
+            answer,
+        } => submit_command(
+            &app,
+            day.unwrap_or_else(next_unsolved_day),
+            part,
+            part_two_only,
+            answer,
+        ),
+        Command::Serve {
+            address,
+            timeout,
+            jobs,
+        } => serve(&app, &address, timeout, jobs.into()),
+        Command::Leaderboard { id } => leaderboard(&app, id),
+        Command::Status => {
+            status(&app);
+            true
+        }
+        Command::History { day, part } => {
+            history(&app, day, part);
+            true
+        }
+    };
+    if succeeded {
+        ExitCode::SUCCESS
+    } else {
+        ExitCode::FAILURE
     }
 }
@@ -0,0 +1,175 @@
+//! Helpers for parsing puzzle inputs with errors that point at the problem.
+
+use std::error;
+use std::fmt;
+use std::str::FromStr;
+
+use regex::Captures;
+use regex::Regex;
+
+/// A problem with a puzzle input, pointing at the offending text.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ParseError {
+    /// The day whose input this is. Solvers do not know their own day number,
+    /// so this is filled in by whoever runs them.
+    pub day: Option<i8>,
+    /// The 1-based line number of the offending text.
+    pub line: usize,
+    /// The 1-based column of the offending text.
+    pub column: usize,
+    /// The offending text, empty if the input ended too early.
+    pub text: String,
+    /// A description of what should have been there instead.
+    pub expected: String,
+}
+
+impl ParseError {
+    pub fn new(line: usize, column: usize, text: &str, expected: &str) -> ParseError {
+        ParseError {
+            day: None,
+            line,
+            column,
+            text: text.to_string(),
+            expected: expected.to_string(),
+        }
+    }
+
+    pub fn with_day(self, day: i8) -> ParseError {
+        ParseError {
+            day: Some(day),
+            ..self
+        }
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if let Some(day) = self.day {
+            write!(f, "day {day}, ")?;
+        }
+        write!(
+            f,
+            "line {}, column {}: expected {}, ",
+            self.line, self.column, self.expected
+        )?;
+        if self.text.is_empty() {
+            write!(f, "found nothing")
+        } else {
+            write!(f, "found \"{}\"", self.text)
+        }
+    }
+}
+
+impl error::Error for ParseError {}
+
+/// Returns the non-blank lines of the input along with their line numbers, or
+/// an error if there are none.
+pub fn input_lines<'a>(
+    input: &'a str,
+    expected: &str,
+) -> Result<Vec<(usize, &'a str)>, ParseError> {
+    let lines: Vec<_> = input
+        .lines()
+        .enumerate()
+        .map(|(idx, line)| (idx + 1, line.trim_end()))
+        .filter(|(_, line)| !line.is_empty())
+        .collect();
+    if lines.is_empty() {
+        return Err(ParseError::new(1, 1, "", expected));
+    }
+    Ok(lines)
+}
+
+/// Returns the only non-blank line of the input along with its line number,
+/// or an error if there is none, or if there are more lines after it.
+pub fn input_line<'a>(input: &'a str, expected: &str) -> Result<(usize, &'a str), ParseError> {
+    let lines = input_lines(input, expected)?;
+    if let Some(&(line_no, line)) = lines.get(1) {
+        return Err(ParseError::new(line_no, 1, line, "the end of the input"));
+    }
+    Ok(lines[0])
+}
+
+/// Parses `text`, found at the given position, as a `T`.
+pub fn parse_at<T: FromStr>(
+    line: usize,
+    column: usize,
+    text: &str,
+    expected: &str,
+) -> Result<T, ParseError> {
+    text.parse()
+        .map_err(|_| ParseError::new(line, column, text, expected))
+}
+
+/// Matches a line against `re`, or reports the whole line if it does not match.
+pub fn captures<'h>(
+    re: &Regex,
+    line: usize,
+    text: &'h str,
+    expected: &str,
+) -> Result<Captures<'h>, ParseError> {
+    re.captures(text)
+        .ok_or_else(|| ParseError::new(line, 1, text, expected))
+}
+
+/// Parses a capture group of a line matched with [`captures`].
+pub fn parse_group<T: FromStr>(
+    captures: &Captures,
+    group: usize,
+    line: usize,
+    expected: &str,
+) -> Result<T, ParseError> {
+    let m = captures.get(group).expect("no such capture group");
+    parse_at(line, m.start() + 1, m.as_str(), expected)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn display() {
+        let error = ParseError::new(3, 7, "x12", "a number");
+        assert_eq!(
+            error.to_string(),
+            "line 3, column 7: expected a number, found \"x12\""
+        );
+        assert_eq!(
+            error.with_day(4).to_string(),
+            "day 4, line 3, column 7: expected a number, found \"x12\""
+        );
+    }
+
+    #[test]
+    fn input_lines_skips_blank_lines() {
+        assert_eq!(
+            input_lines("a\n\nb  \n", "a letter"),
+            Ok(vec![(1, "a"), (3, "b")])
+        );
+        assert_eq!(
+            input_lines(" \n", "a letter"),
+            Err(ParseError::new(1, 1, "", "a letter"))
+        );
+    }
+
+    #[test]
+    fn input_line_rejects_more_lines() {
+        assert_eq!(input_line("\n42\n\n", "a number"), Ok((2, "42")));
+        assert_eq!(
+            input_line("42\n\n43\n44\n", "a number"),
+            Err(ParseError::new(3, 1, "43", "the end of the input"))
+        );
+        assert!(input_line("", "a number").is_err());
+    }
+
+    #[test]
+    fn parse_group_column() {
+        let re = Regex::new(r"(\w+) is (\w+)").unwrap();
+        let c = captures(&re, 2, "answer is forty-two", "a sentence").unwrap();
+        assert_eq!(
+            parse_group::<i64>(&c, 2, 2, "a number"),
+            Err(ParseError::new(2, 11, "forty", "a number"))
+        );
+        assert!(captures(&re, 2, "answer", "a sentence").is_err());
+    }
+}
@@ -0,0 +1,282 @@
+//! Reading puzzle pages from the Advent of Code website.
+
+use std::sync::LazyLock;
+
+use reqwest::Url;
+use scraper::{ElementRef, Html, Node, Selector};
+use serde::{Deserialize, Serialize};
+
+static ARTICLE: LazyLock<Selector> =
+    LazyLock::new(|| Selector::parse("article.day-desc").expect("article selector"));
+static EXAMPLE_INPUT: LazyLock<Selector> =
+    LazyLock::new(|| Selector::parse("pre > code").expect("example input selector"));
+static EMPHASIZED_CODE: LazyLock<Selector> =
+    LazyLock::new(|| Selector::parse("code > em, em > code").expect("emphasized code selector"));
+
+/// The heading of the second part of a puzzle description.
+pub const PART_TWO_HEADING: &str = "--- Part Two ---";
+
+/// Converts the puzzle description on a puzzle page to Markdown: part one,
+/// and part two if it is unlocked. Relative links are resolved against
+/// `page_url`. Returns `None` if the page has no puzzle description.
+pub fn description_markdown(html: &str, page_url: &Url) -> Option<String> {
+    let document = Html::parse_document(html);
+    let converter = Converter { page_url };
+    let mut blocks = vec![];
+    for article in document.select(&ARTICLE) {
+        converter.blocks(article, &mut blocks);
+    }
+    if blocks.is_empty() {
+        return None;
+    }
+    Some(blocks.join("\n\n") + "\n")
+}
+
+/// An example from a puzzle description: an input, and the answer that a
+/// part of the puzzle has for it.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Example {
+    pub part: i8,
+    pub input: String,
+    pub answer: String,
+}
+
+/// The examples on a puzzle page.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Examples {
+    /// Whether the page had part two, so that there is nothing more to find
+    /// on it later.
+    pub part_two_unlocked: bool,
+    pub examples: Vec<Example>,
+}
+
+/// Finds the examples on a puzzle page, at most one per unlocked part.
+///
+/// This relies on how puzzle descriptions are usually written: the first
+/// `<pre><code>` block of a part is its example input, and the last
+/// emphasized code of the part is the answer for it. Part two usually reuses
+/// the example input of part one. Parts where either is missing have no
+/// example.
+pub fn examples(html: &str) -> Examples {
+    let document = Html::parse_document(html);
+    let mut examples: Vec<Example> = vec![];
+    let mut previous_input = None;
+    let mut part_two_unlocked = false;
+    for (part, article) in (1..=2).zip(document.select(&ARTICLE)) {
+        part_two_unlocked = part == 2;
+        let input = article
+            .select(&EXAMPLE_INPUT)
+            .next()
+            .map(text)
+            .or(previous_input);
+        let answer = article.select(&EMPHASIZED_CODE).last().map(text);
+        if let (Some(input), Some(answer)) = (&input, answer) {
+            examples.push(Example {
+                part,
+                input: input.clone(),
+                answer: answer.trim().to_string(),
+            });
+        }
+        previous_input = input;
+    }
+    Examples {
+        part_two_unlocked,
+        examples,
+    }
+}
+
+/// Escapes the characters that would otherwise be read as Markdown.
+fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        if matches!(c, '\\' | '*' | '_' | '`') {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
+
+/// Replaces every run of whitespace with a single space, as a browser does.
+fn collapse_whitespace(text: &str) -> String {
+    let mut collapsed = String::with_capacity(text.len());
+    let mut in_space = false;
+    for c in text.chars() {
+        if c.is_whitespace() {
+            if !in_space {
+                collapsed.push(' ');
+            }
+            in_space = true;
+        } else {
+            collapsed.push(c);
+            in_space = false;
+        }
+    }
+    collapsed
+}
+
+fn text(element: ElementRef) -> String {
+    element.text().collect()
+}
+
+fn code_span(code: &str) -> String {
+    if code.contains('`') {
+        format!("`` {code} ``")
+    } else {
+        format!("`{code}`")
+    }
+}
+
+struct Converter<'a> {
+    page_url: &'a Url,
+}
+
+impl Converter<'_> {
+    /// Converts the block elements in `element` to Markdown blocks.
+    fn blocks(&self, element: ElementRef, blocks: &mut Vec<String>) {
+        for child in element.children() {
+            if let Node::Text(text) = child.value()
+                && !text.trim().is_empty()
+            {
+                blocks.push(escape(collapse_whitespace(text).trim()));
+            }
+            let Some(child) = ElementRef::wrap(child) else {
+                continue;
+            };
+            match child.value().name() {
+                "h2" => blocks.push(format!("## {}", self.inline(child))),
+                "p" => blocks.push(self.inline(child)),
+                "pre" => {
+                    let code = text(child);
+                    blocks.push(format!("```\n{}\n```", code.trim_end_matches('\n')));
+                }
+                "ul" | "ol" => {
+                    let items: Vec<_> = child
+                        .child_elements()
+                        .filter(|item| item.value().name() == "li")
+                        .map(|item| format!("- {}", self.inline(item)))
+                        .collect();
+                    blocks.push(items.join("\n"));
+                }
+                _ => self.blocks(child, blocks),
+            }
+        }
+    }
+
+    /// Converts the contents of `element` to a single line of Markdown.
+    fn inline(&self, element: ElementRef) -> String {
+        let mut line = String::new();
+        for child in element.children() {
+            if let Node::Text(text) = child.value() {
+                line.push_str(&escape(&collapse_whitespace(text)));
+            }
+            let Some(child) = ElementRef::wrap(child) else {
+                continue;
+            };
+            match child.value().name() {
+                // Emphasis inside a code span is shown as an emphasized span.
+                "code" if child.child_elements().any(|e| e.value().name() == "em") => {
+                    line.push_str(&format!("*{}*", code_span(&text(child))));
+                }
+                "code" => line.push_str(&code_span(&text(child))),
+                "em" => {
+                    let emphasized = self.inline(child);
+                    if !emphasized.trim().is_empty() {
+                        line.push_str(&format!("*{}*", emphasized.trim()));
+                    }
+                }
+                "a" => {
+                    let link_text = self.inline(child);
+                    match child
+                        .value()
+                        .attr("href")
+                        .and_then(|href| self.page_url.join(href).ok())
+                    {
+                        Some(url) => line.push_str(&format!("[{}]({url})", link_text.trim())),
+                        None => line.push_str(&link_text),
+                    }
+                }
+                _ => line.push_str(&self.inline(child)),
+            }
+        }
+        collapse_whitespace(&line).trim().to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PAGE: &str = r#"<!DOCTYPE html>
+<html><body><main>
+<article class="day-desc"><h2>--- Day 1: Chronal Calibration ---</h2>
+<p>"We detected some <em>temporal anomalies</em>," one of Santa's Elves at the
+<a href="/2018/support">Temporal Anomaly Research</a> desk tells you.</p>
+<ul>
+<li>Current frequency <code> 0</code>, change of <code>+1</code>; resulting frequency <code>1</code>.</li>
+<li>Current frequency <code>1</code>, change of <code>-2</code>.</li>
+</ul>
+<pre><code>+1
+-2
+</code></pre>
+<p>What is the resulting frequency? <span title="Easter egg">*</span></p>
+</article>
+<p>Your puzzle answer was <code>585</code>.</p>
+<article class="day-desc"><h2 id="part2">--- Part Two ---</h2>
+<p>The first frequency reached twice is <code><em>83173</em></code>.</p>
+</article>
+</main></body></html>
+"#;
+
+    #[test]
+    fn converts_both_parts() {
+        let url = Url::parse("https://adventofcode.com/2018/day/1").unwrap();
+        assert_eq!(
+            description_markdown(PAGE, &url).unwrap(),
+            "## --- Day 1: Chronal Calibration ---\n\
+             \n\
+             \"We detected some *temporal anomalies*,\" one of Santa's Elves at the \
+             [Temporal Anomaly Research](https://adventofcode.com/2018/support) desk tells you.\n\
+             \n\
+             - Current frequency ` 0`, change of `+1`; resulting frequency `1`.\n\
+             - Current frequency `1`, change of `-2`.\n\
+             \n\
+             ```\n\
+             +1\n\
+             -2\n\
+             ```\n\
+             \n\
+             What is the resulting frequency? \\*\n\
+             \n\
+             ## --- Part Two ---\n\
+             \n\
+             The first frequency reached twice is *`83173`*.\n"
+        );
+    }
+
+    #[test]
+    fn finds_examples() {
+        assert_eq!(
+            examples(PAGE).examples,
+            vec![Example {
+                part: 2,
+                input: "+1\n-2\n".into(),
+                answer: "83173".into(),
+            }]
+        );
+        let page = PAGE.replace(
+            "resulting frequency <code>1</code>",
+            "<code><em>1</em></code>",
+        );
+        let found = examples(&page);
+        assert!(found.part_two_unlocked);
+        assert_eq!(found.examples[0].answer, "1");
+        assert_eq!(found.examples[1].input, "+1\n-2\n");
+    }
+
+    #[test]
+    fn page_without_description() {
+        let url = Url::parse("https://adventofcode.com/2018/day/1").unwrap();
+        assert_eq!(description_markdown("<p>Please log in.</p>", &url), None);
+    }
+}
@@ -0,0 +1,159 @@
+//! The list of solvers, and what they solve.
+
+use std::fmt;
+
+use serde::Serialize;
+use serde::Serializer;
+
+use crate::solver::Param;
+use crate::solver::Solver;
+
+/// What kind of puzzle a day is, to help find similar ones.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Tag {
+    Math,
+    Strings,
+    Parsing,
+    Grid,
+    Graph,
+    Tree,
+    Search,
+    Simulation,
+}
+
+impl fmt::Display for Tag {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self {
+            Tag::Math => "math",
+            Tag::Strings => "strings",
+            Tag::Parsing => "parsing",
+            Tag::Grid => "grid",
+            Tag::Graph => "graph",
+            Tag::Tree => "tree",
+            Tag::Search => "search",
+            Tag::Simulation => "simulation",
+        };
+        write!(f, "{name}")
+    }
+}
+
+/// The name of the implementation that every day has, and that is used
+/// unless another one is asked for.
+pub const REFERENCE: &str = "reference";
+
+/// A solver and what it solves. Each `dayNN` module registers one of these
+/// with `inventory::submit!`, so there is no central list to keep up to date.
+///
+/// A day can have several implementations, such as a straightforward
+/// [`REFERENCE`] one and a faster one, which must give the same answers.
+#[derive(Serialize)]
+pub struct SolverInfo {
+    pub year: i16,
+    pub day: i8,
+    /// Tells apart the implementations of the same day.
+    pub implementation: &'static str,
+    /// The title of the puzzle, as shown on the Advent of Code website.
+    pub title: &'static str,
+    pub tags: &'static [Tag],
+    /// Serialized as the parameters of the solver it makes.
+    #[serde(rename = "params", serialize_with = "serialize_params")]
+    pub make_solver: fn() -> Box<dyn Solver>,
+}
+
+impl SolverInfo {
+    pub fn is_reference(&self) -> bool {
+        self.implementation == REFERENCE
+    }
+
+    pub fn params(&self) -> &'static [Param] {
+        (self.make_solver)().params()
+    }
+}
+
+fn serialize_params<S: Serializer>(
+    make_solver: &fn() -> Box<dyn Solver>,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    make_solver().params().serialize(serializer)
+}
+
+inventory::collect!(SolverInfo);
+
+/// Returns every implementation registered for the given year, ordered by
+/// day, with the reference implementation of each day first.
+pub fn all_implementations(year: i16) -> Vec<&'static SolverInfo> {
+    let mut solvers: Vec<_> = inventory::iter::<SolverInfo>
+        .into_iter()
+        .filter(|info| info.year == year)
+        .collect();
+    solvers.sort_by_key(|info| (info.day, !info.is_reference(), info.implementation));
+    solvers
+}
+
+/// Returns the reference solvers for the given year, one per day, ordered by
+/// day.
+pub fn solvers(year: i16) -> Vec<&'static SolverInfo> {
+    all_implementations(year)
+        .into_iter()
+        .filter(|info| info.is_reference())
+        .collect()
+}
+
+/// Returns every implementation for the given day, the reference one first.
+pub fn implementations(year: i16, day: i8) -> Vec<&'static SolverInfo> {
+    all_implementations(year)
+        .into_iter()
+        .filter(|info| info.day == day)
+        .collect()
+}
+
+/// Returns what is known about the reference solver for the given day, if
+/// there is one.
+pub fn find(year: i16, day: i8) -> Option<&'static SolverInfo> {
+    find_implementation(year, day, REFERENCE)
+}
+
+/// Returns what is known about the named implementation for the given day.
+pub fn find_implementation(
+    year: i16,
+    day: i8,
+    implementation: &str,
+) -> Option<&'static SolverInfo> {
+    inventory::iter::<SolverInfo>
+        .into_iter()
+        .find(|info| info.year == year && info.day == day && info.implementation == implementation)
+}
+
+/// Makes a fresh reference solver for the given day, if there is one.
+pub fn solver_for_day(year: i16, day: i8) -> Option<Box<dyn Solver>> {
+    find(year, day).map(|info| (info.make_solver)())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn every_day_registered_once() {
+        let days: Vec<_> = solvers(2018).iter().map(|info| info.day).collect();
+        assert_eq!(days, (1..=15).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn reference_implementation_comes_first() {
+        let names: Vec<_> = implementations(2018, 5)
+            .iter()
+            .map(|info| info.implementation)
+            .collect();
+        assert_eq!(names, vec![REFERENCE, "stack"]);
+        assert!(find_implementation(2018, 5, "stack").is_some());
+        assert!(find_implementation(2018, 5, "quantum").is_none());
+    }
+
+    #[test]
+    fn no_solvers_for_other_years() {
+        assert!(solvers(2017).is_empty());
+        assert!(solver_for_day(2017, 1).is_none());
+    }
+}
@@ -0,0 +1,617 @@
+use std::time::Duration;
+
+use itertools::Itertools;
+use serde::Serialize;
+use serde::Serializer;
+
+use crate::bench::BenchmarkReport;
+use crate::bench::PhaseResult;
+use crate::bench::Statistics;
+use aoc18::aocclient::ValidationResult;
+use aoc18::autosubmit::PuzzleLogEntry;
+use aoc18::leaderboard::Leaderboard;
+use aoc18::registry::SolverInfo;
+use aoc18::solver::Answer;
+use aoc18::trace;
+use aoc18::trace::TraceEntry;
+
+fn serialize_secs<S: Serializer>(duration: &Duration, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.serialize_f64(duration.as_secs_f64())
+}
+
+/// Outcome of solving one part of a puzzle.
+#[derive(Clone, Serialize)]
+#[serde(untagged)]
+pub enum PartOutcome {
+    Solved {
+        answer: Answer,
+        #[serde(rename = "duration_secs", serialize_with = "serialize_secs")]
+        duration: Duration,
+        /// The intermediate results explained by the solver, in explain mode.
+        #[serde(skip_serializing_if = "Vec::is_empty")]
+        trace: Vec<TraceEntry>,
+    },
+    /// The answer was given by the user rather than computed.
+    Provided {
+        answer: Answer,
+    },
+    Failed {
+        error: String,
+    },
+    TimedOut {
+        #[serde(rename = "timeout_secs", serialize_with = "serialize_secs")]
+        timeout: Duration,
+    },
+}
+
+impl PartOutcome {
+    /// Returns the answer or the error as a single line. ASCII art does not fit
+    /// in a table cell, so it is only referred to.
+    pub fn answer(&self) -> String {
+        match self {
+            PartOutcome::Solved { answer, .. } | PartOutcome::Provided { answer } => match answer {
+                Answer::Art(_) => "(ASCII art, see below)".into(),
+                _ => answer.to_string(),
+            },
+            PartOutcome::Failed { error } => format!("FAILED: {error}"),
+            PartOutcome::TimedOut { timeout } => format!("TIMED OUT after {timeout:?}"),
+        }
+    }
+
+    fn time(&self) -> String {
+        match self {
+            PartOutcome::Solved { duration, .. } => format!("{duration:?}"),
+            PartOutcome::Provided { .. }
+            | PartOutcome::Failed { .. }
+            | PartOutcome::TimedOut { .. } => "-".into(),
+        }
+    }
+}
+
+#[derive(Serialize)]
+pub struct SubmissionReport {
+    pub result: ValidationResult,
+    /// Total time spent waiting for the server to stop throttling submissions.
+    #[serde(rename = "throttled_secs", serialize_with = "serialize_secs")]
+    pub throttled: Duration,
+}
+
+#[derive(Serialize)]
+pub struct PartReport {
+    pub part: i8,
+    #[serde(flatten)]
+    pub outcome: PartOutcome,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub submission: Option<SubmissionReport>,
+}
+
+#[derive(Serialize)]
+pub struct DayReport {
+    pub year: i16,
+    pub day: i8,
+    pub parts: Vec<PartReport>,
+}
+
+impl DayReport {
+    fn part(&self, part: i8) -> Option<&PartOutcome> {
+        self.parts
+            .iter()
+            .find(|p| p.part == part)
+            .map(|p| &p.outcome)
+    }
+
+    /// Whether the solver found an answer for every part in the report.
+    pub fn is_solved(&self) -> bool {
+        self.parts
+            .iter()
+            .all(|p| matches!(p.outcome, PartOutcome::Solved { .. }))
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, clap::ValueEnum)]
+pub enum OutputFormat {
+    /// Human-readable tables and log messages.
+    Text,
+    /// A single JSON document on stdout.
+    Json,
+}
+
+/// Prints a plain-text table with left-aligned, padded columns.
+fn print_table<const N: usize>(header: [&str; N], rows: &[[String; N]]) {
+    let mut widths = header.map(str::len);
+    for row in rows.iter() {
+        for (width, cell) in widths.iter_mut().zip(row.iter()) {
+            *width = (*width).max(cell.len());
+        }
+    }
+    let print_row = |row: [&str; N]| {
+        let cells: Vec<_> = row
+            .iter()
+            .zip(widths.iter())
+            .map(|(cell, width)| format!("{cell:<width$}"))
+            .collect();
+        println!("{}", cells.join(" | ").trim_end());
+    };
+    print_row(header);
+    println!(
+        "{}",
+        widths.map(|width| "-".repeat(width)).join("-|-").trim_end()
+    );
+    for row in rows.iter() {
+        print_row(row.each_ref().map(String::as_str));
+    }
+}
+
+fn print_json<T: Serialize + ?Sized>(value: &T) {
+    println!(
+        "{}",
+        serde_json::to_string_pretty(value).expect("serializing report to JSON")
+    );
+}
+
+pub fn print_day(report: &DayReport, format: OutputFormat) {
+    match format {
+        // In text mode, the results of a single day have already been logged.
+        OutputFormat::Text => {}
+        OutputFormat::Json => print_json(report),
+    }
+}
+
+/// Prints the intermediate results that led to the answer of a part.
+pub fn print_trace(part: i8, entries: &[TraceEntry], format: OutputFormat) {
+    match format {
+        OutputFormat::Text => {
+            println!("part {part} explained:");
+            for line in trace::render(entries).lines() {
+                println!("  {line}");
+            }
+        }
+        // In JSON mode, the trace is part of the day report.
+        OutputFormat::Json => {}
+    }
+}
+
+pub fn print_summary(reports: &[DayReport], format: OutputFormat) {
+    if format == OutputFormat::Json {
+        print_json(reports);
+        return;
+    }
+    let rows: Vec<_> = reports
+        .iter()
+        .map(|r| {
+            let (part_one, part_two) = (r.part(1), r.part(2));
+            [
+                r.day.to_string(),
+                part_one.map_or("-".into(), PartOutcome::answer),
+                part_one.map_or("-".into(), PartOutcome::time),
+                part_two.map_or("-".into(), PartOutcome::answer),
+                part_two.map_or("-".into(), PartOutcome::time),
+            ]
+        })
+        .collect();
+    print_table(["day", "part one", "time", "part two", "time"], &rows);
+    for report in reports.iter() {
+        for part in report.parts.iter() {
+            if let PartOutcome::Solved {
+                answer: Answer::Art(art),
+                ..
+            } = &part.outcome
+            {
+                println!("\nday {} part {}:\n{art}", report.day, part.part);
+            }
+        }
+    }
+}
+
+pub fn print_solver_list(solvers: &[&SolverInfo], format: OutputFormat) {
+    if format == OutputFormat::Json {
+        print_json(solvers);
+        return;
+    }
+    let rows: Vec<_> = solvers
+        .iter()
+        .map(|info| {
+            [
+                info.day.to_string(),
+                info.implementation.to_string(),
+                info.title.to_string(),
+                info.tags.iter().join(", "),
+                info.params()
+                    .iter()
+                    .map(|param| format!("{}={}", param.name, param.default))
+                    .join(", "),
+            ]
+        })
+        .collect();
+    print_table(["day", "implementation", "title", "tags", "params"], &rows);
+}
+
+#[derive(Serialize)]
+struct StatisticsReport {
+    #[serde(serialize_with = "serialize_secs")]
+    min: Duration,
+    #[serde(serialize_with = "serialize_secs")]
+    median: Duration,
+    #[serde(serialize_with = "serialize_secs")]
+    mean: Duration,
+    #[serde(serialize_with = "serialize_secs")]
+    stddev: Duration,
+}
+
+impl From<&Statistics> for StatisticsReport {
+    fn from(stats: &Statistics) -> StatisticsReport {
+        StatisticsReport {
+            min: stats.min,
+            median: stats.median,
+            mean: stats.mean,
+            stddev: stats.stddev,
+        }
+    }
+}
+
+/// The statistics of a benchmarked phase, or why it failed.
+#[derive(Serialize)]
+#[serde(untagged)]
+enum PhaseReport<'a> {
+    Timed(StatisticsReport),
+    Failed { error: &'a str },
+}
+
+impl<'a> From<&'a PhaseResult> for PhaseReport<'a> {
+    fn from(phase: &'a PhaseResult) -> PhaseReport<'a> {
+        match phase {
+            Ok(stats) => PhaseReport::Timed(stats.into()),
+            Err(error) => PhaseReport::Failed { error },
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct BenchmarkJson<'a> {
+    year: i16,
+    day: i8,
+    presolve: PhaseReport<'a>,
+    part_one: PhaseReport<'a>,
+    part_two: PhaseReport<'a>,
+}
+
+pub fn print_benchmark_report(year: i16, day: i8, report: &BenchmarkReport, format: OutputFormat) {
+    if format == OutputFormat::Json {
+        print_json(&BenchmarkJson {
+            year,
+            day,
+            presolve: (&report.presolve).into(),
+            part_one: (&report.part_one).into(),
+            part_two: (&report.part_two).into(),
+        });
+        return;
+    }
+    let rows: Vec<_> = [
+        ("presolve", &report.presolve),
+        ("part one", &report.part_one),
+        ("part two", &report.part_two),
+    ]
+    .into_iter()
+    .map(|(phase, result)| match result {
+        Ok(stats) => [
+            phase.to_string(),
+            format!("{:?}", stats.min),
+            format!("{:?}", stats.median),
+            format!("{:?}", stats.mean),
+            format!("{:?}", stats.stddev),
+        ],
+        Err(error) => [
+            phase.to_string(),
+            format!("FAILED: {error}"),
+            "-".into(),
+            "-".into(),
+            "-".into(),
+        ],
+    })
+    .collect();
+    print_table(["phase", "min", "median", "mean", "stddev"], &rows);
+}
+
+#[derive(Serialize)]
+pub struct DayStatus {
+    pub year: i16,
+    pub day: i8,
+    pub part_one: Option<PuzzleLogEntry>,
+    pub part_two: Option<PuzzleLogEntry>,
+}
+
+fn accepted_answer(entry: &Option<PuzzleLogEntry>) -> Option<&str> {
+    entry.as_ref()?.accepted_answer.as_deref()
+}
+
+pub fn print_status(days: &[DayStatus], format: OutputFormat) {
+    if format == OutputFormat::Json {
+        print_json(days);
+        return;
+    }
+    let rows: Vec<_> = days
+        .iter()
+        .map(|status| {
+            let part_one = accepted_answer(&status.part_one);
+            let part_two = accepted_answer(&status.part_two);
+            let stars = part_one.iter().chain(part_two.iter()).count();
+            [
+                status.day.to_string(),
+                "*".repeat(stars),
+                part_one.unwrap_or("-").to_string(),
+                part_two.unwrap_or("-").to_string(),
+            ]
+        })
+        .collect();
+    print_table(["day", "stars", "part one", "part two"], &rows);
+}
+
+/// Prints the members of a private leaderboard, best first. Each day is shown
+/// as '*' with both stars, '+' with one and '.' with none.
+pub fn print_leaderboard(leaderboard: &Leaderboard, format: OutputFormat) {
+    let ranking = leaderboard.ranking();
+    if format == OutputFormat::Json {
+        print_json(&ranking);
+        return;
+    }
+    let rows: Vec<_> = ranking
+        .iter()
+        .enumerate()
+        .map(|(idx, member)| {
+            let days: String = (1..=25)
+                .map(|day| match member.stars_on(day) {
+                    0 => '.',
+                    1 => '+',
+                    _ => '*',
+                })
+                .collect();
+            [
+                (idx + 1).to_string(),
+                member.local_score.to_string(),
+                member.stars.to_string(),
+                days,
+                member.display_name(),
+            ]
+        })
+        .collect();
+    print_table(["rank", "score", "stars", "days 1-25", "name"], &rows);
+}
+
+#[derive(Serialize)]
+struct HistoryJson<'a> {
+    year: i16,
+    day: i8,
+    part: i8,
+    #[serde(flatten)]
+    entry: &'a PuzzleLogEntry,
+}
+
+pub fn print_history(year: i16, day: i8, entries: &[(i8, PuzzleLogEntry)], format: OutputFormat) {
+    if format == OutputFormat::Json {
+        let entries: Vec<_> = entries
+            .iter()
+            .map(|(part, entry)| HistoryJson {
+                year,
+                day,
+                part: *part,
+                entry,
+            })
+            .collect();
+        print_json(&entries);
+        return;
+    }
+    for (part, entry) in entries.iter() {
+        println!("{year} day {day} part {part}:");
+        println!(
+            "  accepted answer: {}",
+            entry.accepted_answer.as_deref().unwrap_or("-")
+        );
+        println!(
+            "  rejected answers: {}",
+            if entry.rejected_answers.is_empty() {
+                "-".into()
+            } else {
+                entry.rejected_answers.join(", ")
+            }
+        );
+        if let Some(lower_bound) = entry.lower_bound {
+            println!("  answer is greater than: {lower_bound}");
+        }
+        if let Some(upper_bound) = entry.upper_bound {
+            println!("  answer is less than: {upper_bound}");
+        }
+    }
+}
+
+/// Whether a part still gives the answer that was accepted.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Verdict {
+    Passed,
+    Failed,
+    /// The answer is ASCII art, while the log has the letters that were read
+    /// from it, so the two cannot be compared.
+    Skipped,
+}
+
+#[derive(Serialize)]
+pub struct VerificationResult {
+    pub year: i16,
+    pub day: i8,
+    pub part: i8,
+    pub expected: String,
+    #[serde(flatten)]
+    pub outcome: PartOutcome,
+    pub result: Verdict,
+}
+
+impl VerificationResult {
+    pub fn new(
+        year: i16,
+        day: i8,
+        part: i8,
+        expected: String,
+        outcome: PartOutcome,
+    ) -> VerificationResult {
+        let result = match &outcome {
+            PartOutcome::Solved {
+                answer: Answer::Art(_),
+                ..
+            } => Verdict::Skipped,
+            PartOutcome::Solved { answer, .. } if answer.to_string() == expected => Verdict::Passed,
+            _ => Verdict::Failed,
+        };
+        VerificationResult {
+            year,
+            day,
+            part,
+            expected,
+            outcome,
+            result,
+        }
+    }
+}
+
+pub fn print_verification(results: &[VerificationResult], format: OutputFormat) {
+    if format == OutputFormat::Json {
+        print_json(results);
+        return;
+    }
+    let rows: Vec<_> = results
+        .iter()
+        .map(|result| {
+            [
+                result.day.to_string(),
+                result.part.to_string(),
+                result.expected.clone(),
+                result.outcome.answer(),
+                result.outcome.time(),
+                match result.result {
+                    Verdict::Passed => "pass",
+                    Verdict::Failed => "FAIL",
+                    Verdict::Skipped => "skipped (ASCII art)",
+                }
+                .to_string(),
+            ]
+        })
+        .collect();
+    print_table(
+        ["day", "part", "expected", "actual", "time", "result"],
+        &rows,
+    );
+    let count = |verdict| {
+        results
+            .iter()
+            .filter(|result| result.result == verdict)
+            .count()
+    };
+    println!(
+        "{} passed, {} failed, {} skipped",
+        count(Verdict::Passed),
+        count(Verdict::Failed),
+        count(Verdict::Skipped)
+    );
+}
+
+/// The outcome of one implementation of a day in cross-check mode.
+#[derive(Serialize)]
+pub struct CrossCheckResult {
+    pub year: i16,
+    pub day: i8,
+    pub part: i8,
+    pub implementation: &'static str,
+    #[serde(flatten)]
+    pub outcome: PartOutcome,
+    /// Whether the answer is the same as that of the reference
+    /// implementation, which must have solved the part.
+    pub agrees: bool,
+}
+
+pub fn print_cross_check(results: &[CrossCheckResult], format: OutputFormat) {
+    if format == OutputFormat::Json {
+        print_json(results);
+        return;
+    }
+    let mut results: Vec<_> = results.iter().collect();
+    results.sort_by_key(|result| result.part);
+    let rows: Vec<_> = results
+        .iter()
+        .map(|result| {
+            [
+                result.part.to_string(),
+                result.implementation.to_string(),
+                result.outcome.answer(),
+                result.outcome.time(),
+                if result.agrees { "agrees" } else { "DIFFERS" }.to_string(),
+            ]
+        })
+        .collect();
+    print_table(
+        ["part", "implementation", "answer", "time", "result"],
+        &rows,
+    );
+    let differing = results.iter().filter(|result| !result.agrees).count();
+    if differing == 0 {
+        println!("all implementations agree");
+    } else {
+        println!("{differing} answers differ from the reference implementation");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn verify(answer: Answer, expected: &str) -> Verdict {
+        let outcome = PartOutcome::Solved {
+            answer,
+            duration: Duration::ZERO,
+            trace: vec![],
+        };
+        VerificationResult::new(2018, 10, 1, expected.into(), outcome).result
+    }
+
+    #[test]
+    fn art_answers_are_not_verified() {
+        assert_eq!(
+            verify(Answer::Art("#..#\n####".into()), "HI"),
+            Verdict::Skipped
+        );
+        assert_eq!(verify(Answer::Integer(10_011), "10011"), Verdict::Passed);
+        assert_eq!(verify(Answer::Integer(10_012), "10011"), Verdict::Failed);
+    }
+
+    #[test]
+    fn day_is_solved_only_if_every_part_is() {
+        let report = |second: PartOutcome| DayReport {
+            year: 2018,
+            day: 1,
+            parts: vec![
+                PartReport {
+                    part: 1,
+                    outcome: PartOutcome::Solved {
+                        answer: Answer::Integer(3),
+                        duration: Duration::ZERO,
+                        trace: vec![],
+                    },
+                    submission: None,
+                },
+                PartReport {
+                    part: 2,
+                    outcome: second,
+                    submission: None,
+                },
+            ],
+        };
+        let solved = PartOutcome::Solved {
+            answer: Answer::Integer(2),
+            duration: Duration::ZERO,
+            trace: vec![],
+        };
+        assert!(report(solved).is_solved());
+        let timeout = Duration::from_secs(1);
+        assert!(!report(PartOutcome::TimedOut { timeout }).is_solved());
+        let error = "panicked".to_string();
+        assert!(!report(PartOutcome::Failed { error }).is_solved());
+    }
+}
@@ -0,0 +1,274 @@
+use std::any::Any;
+use std::io::{self, IsTerminal};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+
+use crate::report::PartOutcome;
+use crate::timeit;
+use crate::watchdog::{self, Watched};
+use aoc18::context::{self, Cancelled, Context};
+use aoc18::registry::{self, REFERENCE};
+use aoc18::solver::Solver;
+use aoc18::solver::set_params;
+use aoc18::visualize::Visualization;
+
+/// Describes why a solver unwound: it was interrupted, or it panicked.
+pub fn failure_message(payload: Box<dyn Any + Send>) -> String {
+    if payload.is::<Cancelled>() {
+        "interrupted".into()
+    } else if let Some(message) = payload.downcast_ref::<&str>() {
+        message.to_string()
+    } else if let Some(message) = payload.downcast_ref::<String>() {
+        message.clone()
+    } else {
+        "solver panicked".into()
+    }
+}
+
+/// Runs presolve and the parts of one day's puzzle under the watchdog, so that
+/// a solver that panics or runs away does not take the whole program with it.
+///
+/// A part that panics or times out takes its solver with it, so the next part
+/// gets a fresh solver with presolve run again. A part that times out is also
+/// cancelled, so that it stops at its next progress report.
+pub struct PartRunner {
+    year: i16,
+    day: i8,
+    input: Arc<str>,
+    timeout: Option<Duration>,
+    implementation: String,
+    params: Vec<(String, i64)>,
+    show_progress: bool,
+    visualization: Option<Visualization>,
+    explain: bool,
+    solver: Option<Box<dyn Solver>>,
+}
+
+impl PartRunner {
+    /// Creates a runner for the given day, which must have a solver.
+    pub fn new(year: i16, day: i8, input: &str, timeout: Option<Duration>) -> PartRunner {
+        PartRunner {
+            year,
+            day,
+            input: input.into(),
+            timeout,
+            implementation: REFERENCE.to_string(),
+            params: vec![],
+            show_progress: false,
+            visualization: None,
+            explain: false,
+            solver: None,
+        }
+    }
+
+    /// Runs the named implementation of the day, which must exist, instead of
+    /// the reference one.
+    pub fn with_implementation(mut self, implementation: &str) -> PartRunner {
+        self.implementation = implementation.to_string();
+        self
+    }
+
+    /// Overrides solver parameters, which must have been checked with
+    /// [`set_params`] already.
+    pub fn with_params(mut self, params: &[(String, i64)]) -> PartRunner {
+        self.params = params.to_vec();
+        self
+    }
+
+    /// Shows the progress reported by solvers on a status line, if stderr is
+    /// a terminal.
+    pub fn with_progress(mut self) -> PartRunner {
+        self.show_progress = io::stderr().is_terminal();
+        self
+    }
+
+    /// Records the frames of solvers that can be visualized.
+    pub fn with_visualization(mut self, visualization: Option<Visualization>) -> PartRunner {
+        self.visualization = visualization;
+        self
+    }
+
+    /// Keeps the intermediate results that solvers explain, to show with the
+    /// answers.
+    pub fn with_explain(mut self) -> PartRunner {
+        self.explain = true;
+        self
+    }
+
+    fn context(&self, part: i8) -> Context {
+        let mut ctx = Context::default();
+        if self.explain {
+            ctx = ctx.with_trace();
+        }
+        if self.show_progress {
+            let day = self.day;
+            ctx = ctx.with_reporter(move |message| {
+                eprint!("\r\x1b[2Kday {day} part {part}: {message}");
+            });
+        }
+        if let Some(visualization) = &self.visualization {
+            match visualization.open(self.day, part) {
+                Ok(sink) => ctx = ctx.with_recorder(sink, visualization.max_frames),
+                Err(e) => log::error!("cannot record frames: {e}"),
+            }
+        }
+        ctx
+    }
+
+    fn finish_recording(&self, part: i8, ctx: Context) {
+        if self.visualization.is_none() {
+            return;
+        }
+        match ctx.finish_recording() {
+            Ok(0) => log::warn!("day {} part {part} has no visualization", self.day),
+            Ok(frames) => log::info!("recorded {frames} frames of day {} part {part}", self.day),
+            Err(e) => log::error!("cannot record frames: {e}"),
+        }
+    }
+
+    /// Turns a panic or timeout into the corresponding outcome.
+    fn check<T>(&self, watched: Watched<T>) -> Result<T, PartOutcome> {
+        match watched {
+            Watched::Finished(Ok(result)) => Ok(result),
+            Watched::Finished(Err(payload)) => Err(PartOutcome::Failed {
+                error: failure_message(payload),
+            }),
+            Watched::TimedOut => Err(PartOutcome::TimedOut {
+                timeout: self.timeout.expect("timed out without a timeout"),
+            }),
+        }
+    }
+
+    /// Creates a fresh solver and runs presolve on it. Invalid input is
+    /// reported as a failure.
+    pub fn presolve(&mut self) -> Result<(), PartOutcome> {
+        let info = registry::find_implementation(self.year, self.day, &self.implementation)
+            .expect("no such implementation for this day");
+        self.presolve_with((info.make_solver)())
+    }
+
+    /// Runs presolve on the given solver, which the parts are then solved
+    /// with, instead of on a fresh one from the registry.
+    pub fn presolve_with(&mut self, mut solver: Box<dyn Solver>) -> Result<(), PartOutcome> {
+        set_params(solver.as_mut(), &self.params).expect("invalid solver parameters");
+        let input = self.input.clone();
+        let (solver, parsed) = self.check(watchdog::run(self.timeout, move || {
+            let parsed = solver.presolve(&input);
+            (solver, parsed)
+        }))?;
+        if let Err(e) = parsed {
+            return Err(PartOutcome::Failed {
+                error: format!("cannot parse puzzle input: {}", e.with_day(self.day)),
+            });
+        }
+        self.solver = Some(solver);
+        Ok(())
+    }
+
+    pub fn run(&mut self, part: i8) -> PartOutcome {
+        if context::is_interrupted() {
+            return PartOutcome::Failed {
+                error: "interrupted".into(),
+            };
+        }
+        if self.solver.is_none()
+            && let Err(outcome) = self.presolve()
+        {
+            return outcome;
+        }
+        let mut solver = self.solver.take().unwrap();
+        let ctx = self.context(part);
+        let cancellation = ctx.cancellation();
+        let watched = watchdog::run(self.timeout, move || {
+            let (answer, duration) = timeit(|| match part {
+                1 => solver.solve_part_one(&ctx),
+                _ => solver.solve_part_two(&ctx),
+            });
+            (solver, answer, duration, ctx)
+        });
+        if matches!(watched, Watched::TimedOut) {
+            cancellation.store(true, Ordering::Relaxed);
+        }
+        if self.show_progress {
+            eprint!("\r\x1b[2K");
+        }
+        match self.check(watched) {
+            Ok((solver, answer, duration, ctx)) => {
+                let trace = ctx.take_trace();
+                if self.explain && trace.is_empty() {
+                    log::warn!("day {} part {part} has nothing to explain", self.day);
+                }
+                self.finish_recording(part, ctx);
+                self.solver = Some(solver);
+                PartOutcome::Solved {
+                    answer,
+                    duration,
+                    trace,
+                }
+            }
+            Err(outcome) => outcome,
+        }
+    }
+}
+
+/// Applies `f` to every item on up to `jobs` worker threads, and returns the
+/// results in the order of the items.
+pub fn parallel_map<T, R, F>(jobs: usize, items: &[T], f: F) -> Vec<R>
+where
+    T: Sync,
+    R: Send,
+    F: Fn(&T) -> R + Sync,
+{
+    let next_item = AtomicUsize::new(0);
+    let results: Mutex<Vec<Option<R>>> = Mutex::new(items.iter().map(|_| None).collect());
+    thread::scope(|scope| {
+        for _ in 0..jobs.min(items.len()) {
+            scope.spawn(|| {
+                loop {
+                    let idx = next_item.fetch_add(1, Ordering::Relaxed);
+                    let Some(item) = items.get(idx) else {
+                        break;
+                    };
+                    let result = f(item);
+                    results.lock().unwrap()[idx] = Some(result);
+                }
+            });
+        }
+    });
+    results
+        .into_inner()
+        .unwrap()
+        .into_iter()
+        .map(|result| result.expect("every item has been processed"))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parallel_map_keeps_order() {
+        let items: Vec<u64> = (0..20).collect();
+        let results = parallel_map(4, &items, |&v| {
+            thread::sleep(Duration::from_millis(20 - v));
+            v * 2
+        });
+        assert_eq!(results, (0..20).map(|v| v * 2).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn parallel_map_more_jobs_than_items() {
+        assert_eq!(parallel_map(8, &[1, 2], |v| v + 1), vec![2, 3]);
+        assert!(parallel_map(8, &[] as &[i32], |v| v + 1).is_empty());
+    }
+
+    #[test]
+    fn runaway_part_times_out() {
+        // The frequency never repeats, so part two only stops when cancelled.
+        let mut runner = PartRunner::new(2018, 1, "+1\n", Some(Duration::from_millis(100)));
+        assert!(matches!(runner.run(2), PartOutcome::TimedOut { .. }));
+    }
+}
@@ -0,0 +1,216 @@
+use std::thread;
+use std::time::Duration;
+
+use serde::Serialize;
+use tiny_http::{Header, Method, Request, Response, Server};
+
+use crate::report::{DayReport, PartOutcome, PartReport};
+use crate::runner::PartRunner;
+use aoc18::context;
+use aoc18::registry;
+
+/// How often idle workers check whether the server should shut down.
+const POLL_INTERVAL: Duration = Duration::from_millis(200);
+
+#[derive(Serialize)]
+struct ErrorBody<'a> {
+    error: &'a str,
+}
+
+/// A response to send back: the status code and the JSON body.
+#[derive(Debug, PartialEq)]
+struct Reply {
+    status: u16,
+    body: String,
+}
+
+impl Reply {
+    fn json<T: Serialize + ?Sized>(value: &T) -> Reply {
+        Reply {
+            status: 200,
+            body: serde_json::to_string(value).expect("serializing reply"),
+        }
+    }
+
+    fn error(status: u16, error: &str) -> Reply {
+        Reply {
+            status,
+            ..Reply::json(&ErrorBody { error })
+        }
+    }
+}
+
+/// Solves puzzles sent over HTTP, so that other tools can use the solvers
+/// without running this program for each input:
+///
+/// - `GET /days` lists the solvers, as `solve --list` does.
+/// - `POST /solve/{day}` solves the puzzle input in the body, and returns the
+///   answers and timings of both parts, as `solve --format json` does.
+///
+/// Every request gets a fresh solver, so concurrent requests for the same day
+/// do not share any state.
+pub struct SolveService {
+    pub year: i16,
+    /// Give up on a part if it takes longer than this.
+    pub timeout: Option<Duration>,
+}
+
+impl SolveService {
+    fn handle(&self, method: &Method, url: &str, body: &[u8]) -> Reply {
+        let path = url.split_once('?').map_or(url, |(path, _)| path);
+        match (method, path) {
+            (Method::Get, "/days") => Reply::json(&registry::solvers(self.year)),
+            (Method::Post, path) if path.starts_with("/solve/") => {
+                let Ok(day) = path["/solve/".len()..].parse() else {
+                    return Reply::error(400, "expected /solve/{day}");
+                };
+                let Ok(input) = std::str::from_utf8(body) else {
+                    return Reply::error(400, "the puzzle input is not valid UTF-8");
+                };
+                self.solve(day, input)
+            }
+            (_, "/days") => Reply::error(405, "use GET /days"),
+            (_, path) if path.starts_with("/solve/") => Reply::error(405, "use POST /solve/{day}"),
+            _ => Reply::error(404, "not found"),
+        }
+    }
+
+    fn solve(&self, day: i8, input: &str) -> Reply {
+        let Some(solver) = registry::solver_for_day(self.year, day) else {
+            let error = format!("there is no solver for day {day} of {}", self.year);
+            return Reply::error(404, &error);
+        };
+        let mut runner = PartRunner::new(self.year, day, input, self.timeout);
+        if let Err(outcome) = runner.presolve_with(solver) {
+            return match outcome {
+                PartOutcome::Failed { error } => Reply::error(422, &error),
+                _ => Reply::error(500, &format!("presolve failed: {}", outcome.answer())),
+            };
+        }
+        let parts = [1, 2]
+            .map(|part| PartReport {
+                part,
+                outcome: runner.run(part),
+                submission: None,
+            })
+            .into();
+        Reply::json(&DayReport {
+            year: self.year,
+            day,
+            parts,
+        })
+    }
+
+    fn respond(&self, mut request: Request) {
+        let mut body = vec![];
+        let reply = match request.as_reader().read_to_end(&mut body) {
+            Ok(_) => self.handle(request.method(), request.url(), &body),
+            Err(e) => Reply::error(400, &format!("cannot read request body: {e}")),
+        };
+        log::info!("{} {} -> {}", request.method(), request.url(), reply.status);
+        let content_type = Header::from_bytes("Content-Type", "application/json").unwrap();
+        let response = Response::from_string(reply.body)
+            .with_status_code(reply.status)
+            .with_header(content_type);
+        if let Err(e) = request.respond(response) {
+            log::warn!("cannot send response: {e}");
+        }
+    }
+
+    /// Answers requests on up to `jobs` worker threads, until the user
+    /// presses Ctrl-C.
+    pub fn serve(&self, server: &Server, jobs: usize) {
+        thread::scope(|scope| {
+            for _ in 0..jobs {
+                scope.spawn(|| {
+                    while !context::is_interrupted() {
+                        match server.recv_timeout(POLL_INTERVAL) {
+                            Ok(Some(request)) => self.respond(request),
+                            Ok(None) => {}
+                            Err(e) => log::error!("cannot receive request: {e}"),
+                        }
+                    }
+                });
+            }
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::{Read, Write};
+    use std::net::TcpStream;
+
+    fn service() -> SolveService {
+        SolveService {
+            year: 2018,
+            timeout: None,
+        }
+    }
+
+    fn body(reply: &Reply) -> serde_json::Value {
+        serde_json::from_str(&reply.body).unwrap()
+    }
+
+    #[test]
+    fn solves_posted_input() {
+        let reply = service().handle(&Method::Post, "/solve/1", b"+1\n-2\n+3\n+1\n");
+        assert_eq!(reply.status, 200);
+        let report = body(&reply);
+        assert_eq!(report["day"], 1);
+        assert_eq!(report["parts"][0]["answer"], 3);
+        assert_eq!(report["parts"][1]["answer"], 2);
+        assert!(report["parts"][1]["duration_secs"].is_number());
+    }
+
+    #[test]
+    fn runaway_request_gets_a_timeout_response() {
+        let service = SolveService {
+            timeout: Some(Duration::from_millis(100)),
+            ..service()
+        };
+        let server = Server::http("127.0.0.1:0").unwrap();
+        let address = server.server_addr().to_ip().unwrap();
+        let client = thread::spawn(move || {
+            let mut stream = TcpStream::connect(address).unwrap();
+            // The frequency never repeats, so part two never finishes.
+            stream
+                .write_all(
+                    b"POST /solve/1 HTTP/1.1\r\nConnection: close\r\nContent-Length: 3\r\n\r\n+1\n",
+                )
+                .unwrap();
+            let mut response = String::new();
+            stream.read_to_string(&mut response).unwrap();
+            response
+        });
+        service.respond(server.recv().unwrap());
+        let response = client.join().unwrap();
+        let (head, body) = response.split_once("\r\n\r\n").unwrap();
+        assert!(head.starts_with("HTTP/1.1 200"));
+        assert!(head.contains("Content-Type: application/json"));
+        let report: serde_json::Value = serde_json::from_str(body).unwrap();
+        assert_eq!(report["parts"][0]["answer"], 1);
+        assert_eq!(report["parts"][1]["timeout_secs"], 0.1);
+    }
+
+    #[test]
+    fn lists_days() {
+        let reply = service().handle(&Method::Get, "/days", b"");
+        assert_eq!(reply.status, 200);
+        assert_eq!(body(&reply).as_array().unwrap().len(), 15);
+    }
+
+    #[test]
+    fn rejects_bad_requests() {
+        let service = service();
+        let status = |method, url, body: &[u8]| service.handle(&method, url, body).status;
+        assert_eq!(status(Method::Post, "/solve/one", b""), 400);
+        assert_eq!(status(Method::Post, "/solve/1", b"\xff"), 400);
+        assert_eq!(status(Method::Post, "/solve/24", b""), 404);
+        assert_eq!(status(Method::Post, "/solve/1", b"+x\n"), 422);
+        assert_eq!(status(Method::Get, "/solve/1", b""), 405);
+        assert_eq!(status(Method::Post, "/days", b""), 405);
+        assert_eq!(status(Method::Get, "/", b""), 404);
+    }
+}
@@ -1,12 +1,197 @@
+//! The interface that every day's solver implements.
+
+use std::fmt;
+
+use itertools::Itertools;
+use serde::Serialize;
+use serde::Serializer;
+
+use crate::context::Context;
+use crate::parse::ParseError;
+
+/// The answer to one part of a puzzle.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Answer {
+    /// A number, such as a count, a sum or a product.
+    Integer(i64),
+    /// A string of letters or digits that is not a number, e.g. a step order.
+    Text(String),
+    /// A position on a grid, submitted as comma-separated values like `33,45`.
+    Coordinates(Vec<i64>),
+    /// A message drawn with characters on a grid. It has to be read by a human,
+    /// so it is never submitted automatically.
+    Art(String),
+}
+
+impl Answer {
+    /// Interprets an answer given by the user: integers become `Integer`, and
+    /// anything else is `Text`. Answers such as `0124515891` or `+5` stay
+    /// `Text`, because they would not be submitted as written otherwise.
+    pub fn parse(answer: &str) -> Answer {
+        match answer.parse::<i64>() {
+            Ok(value) if value.to_string() == answer => Answer::Integer(value),
+            _ => Answer::Text(answer.to_string()),
+        }
+    }
+
+    /// Returns the answer if it is a number.
+    pub fn as_integer(&self) -> Option<i64> {
+        match self {
+            Answer::Integer(value) => Some(*value),
+            _ => None,
+        }
+    }
+
+    /// Whether the answer can be sent to the server as is.
+    pub fn is_submittable(&self) -> bool {
+        match self {
+            Answer::Integer(_) | Answer::Coordinates(_) => true,
+            Answer::Text(text) => !text.is_empty(),
+            Answer::Art(_) => false,
+        }
+    }
+}
+
+impl fmt::Display for Answer {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Answer::Integer(value) => write!(f, "{value}"),
+            Answer::Text(text) | Answer::Art(text) => write!(f, "{text}"),
+            Answer::Coordinates(coords) => write!(f, "{}", coords.iter().join(",")),
+        }
+    }
+}
+
+/// Integers are serialized as numbers, everything else in its submission form.
+impl Serialize for Answer {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            Answer::Integer(value) => serializer.serialize_i64(*value),
+            _ => serializer.collect_str(self),
+        }
+    }
+}
+
+macro_rules! answer_from_integer {
+    ($($t:ty),*) => {
+        $(
+            impl From<$t> for Answer {
+                fn from(value: $t) -> Answer {
+                    Answer::Integer(i64::try_from(value).expect("answer does not fit in i64"))
+                }
+            }
+        )*
+    };
+}
+
+answer_from_integer!(i32, i64, isize, u64, usize);
+
+/// A tunable value that a solver reads instead of a hard-coded constant, so
+/// that puzzle variants and examples can be run without recompiling.
+#[derive(Debug, Serialize)]
+pub struct Param {
+    pub name: &'static str,
+    pub description: &'static str,
+    pub default: i64,
+    /// The range of values that make sense for this parameter.
+    pub min: i64,
+    pub max: i64,
+}
+
 /// A solver for both parts of one day's Advent of Code puzzles.
-pub trait Solver {
+///
+/// Solvers must be `Send` so that parts can run on a watchdog thread.
+pub trait Solver: Send {
     /// Preliminary computations for both parts of the puzzle, input parsing,
-    /// building necessary data structures, etc.
-    fn presolve(&mut self, input: &str);
+    /// building necessary data structures, etc. Fails if the input is not
+    /// what the puzzle describes.
+    fn presolve(&mut self, input: &str) -> Result<(), ParseError>;
 
     /// Solve and return the solution for the first part of the puzzle.
-    fn solve_part_one(&mut self) -> String;
+    /// Long-running solvers should report their progress through `ctx`.
+    fn solve_part_one(&mut self, ctx: &Context) -> Answer;
 
     /// Solve and return the solution for the second part of the puzzle.
-    fn solve_part_two(&mut self) -> String;
+    fn solve_part_two(&mut self, ctx: &Context) -> Answer;
+
+    /// The tunable parameters of this solver.
+    fn params(&self) -> &'static [Param] {
+        &[]
+    }
+
+    /// Sets a parameter declared in [`Solver::params`] to a value within its
+    /// range. Called before presolve.
+    fn set_param(&mut self, name: &str, _value: i64) {
+        unreachable!("{name} is not a parameter of this solver")
+    }
+}
+
+/// Sets all parameters of a new solver to their defaults.
+pub fn with_default_params<S: Solver>(mut solver: S) -> S {
+    for param in solver.params() {
+        solver.set_param(param.name, param.default);
+    }
+    solver
+}
+
+/// Overrides parameters of a solver, checking that it has them and that the
+/// values are in range.
+pub fn set_params(solver: &mut dyn Solver, overrides: &[(String, i64)]) -> Result<(), String> {
+    for (name, value) in overrides.iter() {
+        let Some(param) = solver.params().iter().find(|param| param.name == name) else {
+            let known = solver.params().iter().map(|param| param.name).join(", ");
+            return Err(if known.is_empty() {
+                format!("unknown parameter {name}: this solver has no parameters")
+            } else {
+                format!("unknown parameter {name}: expected one of {known}")
+            });
+        };
+        if *value < param.min || *value > param.max {
+            let range = if param.max == i64::MAX {
+                format!("at least {}", param.min)
+            } else {
+                format!("between {} and {}", param.min, param.max)
+            };
+            return Err(format!("parameter {name} must be {range}, got {value}"));
+        }
+        solver.set_param(name, *value);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::day07;
+
+    #[test]
+    fn set_params_checks_names_and_ranges() {
+        let mut solver = day07::solver();
+        assert!(set_params(&mut solver, &[("work_time".into(), 0)]).is_ok());
+        assert_eq!(
+            set_params(&mut solver, &[("workers".into(), 2)]),
+            Err("unknown parameter workers: expected one of worker_count, work_time".into())
+        );
+        assert!(set_params(&mut solver, &[("worker_count".into(), 0)]).is_err());
+    }
+
+    #[test]
+    fn answer_display() {
+        assert_eq!(Answer::from(-3i64).to_string(), "-3");
+        assert_eq!(
+            Answer::Coordinates(vec![90, 269, 16]).to_string(),
+            "90,269,16"
+        );
+        assert_eq!(Answer::parse("CABDFE"), Answer::Text("CABDFE".into()));
+    }
+
+    #[test]
+    fn parse_keeps_answers_as_written() {
+        assert_eq!(Answer::parse("-3"), Answer::Integer(-3));
+        assert_eq!(
+            Answer::parse("0124515891"),
+            Answer::Text("0124515891".into())
+        );
+        assert_eq!(Answer::parse("+5"), Answer::Text("+5".into()));
+    }
 }
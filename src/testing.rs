@@ -0,0 +1,22 @@
+//! Helpers shared by the tests of the solvers.
+
+use std::panic;
+use std::sync::atomic::Ordering;
+use std::thread;
+use std::time::Duration;
+
+use crate::context::Cancelled;
+use crate::context::Context;
+
+/// Runs `solve` with a context that gets cancelled shortly after it starts,
+/// and checks that it stops by unwinding with [`Cancelled`].
+pub fn assert_cancellable<T>(solve: impl FnOnce(&Context) -> T) {
+    let ctx = Context::default();
+    let cancellation = ctx.cancellation();
+    thread::spawn(move || {
+        thread::sleep(Duration::from_millis(50));
+        cancellation.store(true, Ordering::Relaxed);
+    });
+    let result = panic::catch_unwind(panic::AssertUnwindSafe(|| solve(&ctx)));
+    assert!(result.is_err_and(|payload| payload.is::<Cancelled>()));
+}
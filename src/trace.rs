@@ -0,0 +1,114 @@
+//! Step-by-step explanations of how solvers got to their answers.
+
+use serde::Serialize;
+
+/// A named intermediate result that a solver recorded in explain mode, or a
+/// section grouping several of them.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct TraceEntry {
+    pub name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub value: Option<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub children: Vec<TraceEntry>,
+}
+
+/// Collects the entries of a trace as a solver records them.
+#[derive(Default)]
+pub struct Trace {
+    entries: Vec<TraceEntry>,
+    /// Sections that have been opened but not closed yet, innermost last.
+    open_sections: Vec<TraceEntry>,
+}
+
+impl Trace {
+    fn current(&mut self) -> &mut Vec<TraceEntry> {
+        match self.open_sections.last_mut() {
+            Some(section) => &mut section.children,
+            None => &mut self.entries,
+        }
+    }
+
+    /// Adds a named value to the innermost open section.
+    pub fn add(&mut self, name: &str, value: String) {
+        self.current().push(TraceEntry {
+            name: name.to_string(),
+            value: Some(value),
+            children: vec![],
+        });
+    }
+
+    /// Opens a section: entries added until it is closed go into it.
+    pub fn open_section(&mut self, name: &str) {
+        self.open_sections.push(TraceEntry {
+            name: name.to_string(),
+            value: None,
+            children: vec![],
+        });
+    }
+
+    /// Closes the innermost open section.
+    pub fn close_section(&mut self) {
+        let section = self.open_sections.pop().expect("no open section");
+        self.current().push(section);
+    }
+
+    /// Returns the entries recorded so far, closing any open sections, which
+    /// is what a solver that was cancelled halfway leaves behind.
+    pub fn into_entries(mut self) -> Vec<TraceEntry> {
+        while !self.open_sections.is_empty() {
+            self.close_section();
+        }
+        self.entries
+    }
+}
+
+/// Renders a trace as an indented report, one entry per line. Values that span
+/// several lines are indented as a block under their name.
+pub fn render(entries: &[TraceEntry]) -> String {
+    fn render_into(entries: &[TraceEntry], depth: usize, out: &mut String) {
+        let indent = "  ".repeat(depth);
+        for entry in entries {
+            match &entry.value {
+                Some(value) if value.contains('\n') => {
+                    out.push_str(&format!("{indent}{}:\n", entry.name));
+                    for line in value.lines() {
+                        out.push_str(&format!("{indent}  {line}\n"));
+                    }
+                }
+                Some(value) => out.push_str(&format!("{indent}{}: {value}\n", entry.name)),
+                None => out.push_str(&format!("{indent}{}\n", entry.name)),
+            }
+            render_into(&entry.children, depth + 1, out);
+        }
+    }
+    let mut out = String::new();
+    render_into(entries, 0, &mut out);
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sections_nest() {
+        let mut trace = Trace::default();
+        trace.add("guards", "3".into());
+        trace.open_section("attack power 4");
+        trace.add("rounds", "47".into());
+        trace.open_section("survivors");
+        trace.add("elves", "E1\nE2".into());
+        let entries = trace.into_entries();
+        assert_eq!(
+            render(&entries),
+            "guards: 3\n\
+             attack power 4\n\
+             \x20 rounds: 47\n\
+             \x20 survivors\n\
+             \x20   elves:\n\
+             \x20     E1\n\
+             \x20     E2\n"
+        );
+    }
+}
@@ -0,0 +1,442 @@
+//! Frames drawn by simulations, and the ways to show them.
+
+use std::fs;
+use std::fs::File;
+use std::io;
+use std::io::BufWriter;
+use std::io::Write;
+use std::path::PathBuf;
+use std::thread;
+use std::time::Duration;
+
+/// Size of the square of pixels that one cell becomes in images.
+const CELL_PIXELS: usize = 4;
+
+/// The color of a cell, from the colors that every terminal has.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Color {
+    Red,
+    Green,
+    Yellow,
+    Blue,
+    Magenta,
+    Cyan,
+    White,
+}
+
+impl Color {
+    const ALL: [Color; 7] = [
+        Color::Red,
+        Color::Green,
+        Color::Yellow,
+        Color::Blue,
+        Color::Magenta,
+        Color::Cyan,
+        Color::White,
+    ];
+
+    fn ansi_code(self) -> u8 {
+        match self {
+            Color::Red => 31,
+            Color::Green => 32,
+            Color::Yellow => 33,
+            Color::Blue => 34,
+            Color::Magenta => 35,
+            Color::Cyan => 36,
+            Color::White => 37,
+        }
+    }
+
+    fn rgb(self) -> [u8; 3] {
+        match self {
+            Color::Red => [0xe0, 0x30, 0x30],
+            Color::Green => [0x30, 0xc0, 0x30],
+            Color::Yellow => [0xf0, 0xd0, 0x20],
+            Color::Blue => [0x30, 0x60, 0xe0],
+            Color::Magenta => [0xc0, 0x30, 0xc0],
+            Color::Cyan => [0x30, 0xc0, 0xc0],
+            Color::White => [0xff, 0xff, 0xff],
+        }
+    }
+}
+
+/// A character on the grid of a frame. Cells without a color are drawn in
+/// the default color of the terminal, or in gray in images.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Cell {
+    pub ch: char,
+    pub color: Option<Color>,
+}
+
+/// Palette shared by all image formats: cells without a color are black if
+/// they are blank and gray otherwise.
+const BLACK: u8 = 0;
+const GRAY: u8 = 1;
+
+impl Cell {
+    const BLANK: Cell = Cell {
+        ch: ' ',
+        color: None,
+    };
+
+    fn palette_index(self) -> u8 {
+        match self.color {
+            Some(color) => 2 + Color::ALL.iter().position(|c| *c == color).unwrap() as u8,
+            None if self.ch == ' ' || self.ch == '.' => BLACK,
+            None => GRAY,
+        }
+    }
+}
+
+fn palette() -> Vec<[u8; 3]> {
+    let mut palette = vec![[0, 0, 0], [0x80, 0x80, 0x80]];
+    palette.extend(Color::ALL.iter().map(|color| color.rgb()));
+    palette
+}
+
+/// A picture of a simulation at one step, made of characters on a grid.
+/// Frames know where they are, so that a simulation whose contents move
+/// around can be drawn on a common canvas.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Frame {
+    left: i64,
+    top: i64,
+    width: usize,
+    height: usize,
+    cells: Vec<Cell>,
+}
+
+impl Frame {
+    /// Creates a frame of blank cells with its top left corner at (`left`, `top`).
+    pub fn new(left: i64, top: i64, width: usize, height: usize) -> Frame {
+        Frame {
+            left,
+            top,
+            width,
+            height,
+            cells: vec![Cell::BLANK; width * height],
+        }
+    }
+
+    fn index(&self, x: i64, y: i64) -> Option<usize> {
+        let (x, y) = (x - self.left, y - self.top);
+        if x < 0 || y < 0 || x as usize >= self.width || y as usize >= self.height {
+            return None;
+        }
+        Some(y as usize * self.width + x as usize)
+    }
+
+    /// Sets the cell at (`x`, `y`). Cells outside of the frame are ignored.
+    pub fn set(&mut self, x: i64, y: i64, ch: char, color: Option<Color>) {
+        if let Some(idx) = self.index(x, y) {
+            self.cells[idx] = Cell { ch, color };
+        }
+    }
+
+    fn rows(&self) -> impl Iterator<Item = &[Cell]> {
+        self.cells.chunks(self.width.max(1))
+    }
+}
+
+/// Implemented by the state of simulations that can be drawn.
+pub trait Visualize {
+    fn frame(&self) -> Frame;
+}
+
+/// Where recorded frames go.
+pub trait FrameSink: Send {
+    fn write_frame(&mut self, frame: &Frame) -> io::Result<()>;
+
+    /// Called once after the last frame.
+    fn finish(self: Box<Self>) -> io::Result<()>;
+}
+
+/// How recorded frames are shown.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, clap::ValueEnum)]
+pub enum VisualizationFormat {
+    /// Replay the frames in the terminal as they are recorded.
+    Terminal,
+    /// Write every frame as a PPM image.
+    Ppm,
+    /// Write an animated GIF.
+    Gif,
+}
+
+/// How to record the frames of a solver.
+#[derive(Clone, Debug)]
+pub struct Visualization {
+    pub format: VisualizationFormat,
+    /// The directory to write images to.
+    pub output_dir: PathBuf,
+    /// Recording stops after this many frames per part.
+    pub max_frames: usize,
+    pub frame_delay: Duration,
+}
+
+impl Visualization {
+    /// Opens a sink for the frames of one part of a puzzle.
+    pub fn open(&self, day: i8, part: i8) -> io::Result<Box<dyn FrameSink>> {
+        let name = format!("day{day:02}-part{part}");
+        Ok(match self.format {
+            VisualizationFormat::Terminal => Box::new(TerminalReplay {
+                delay: self.frame_delay,
+            }),
+            VisualizationFormat::Ppm => {
+                fs::create_dir_all(&self.output_dir)?;
+                Box::new(Canvas::new(ImageWriter::Ppm {
+                    dir: self.output_dir.clone(),
+                    name,
+                }))
+            }
+            VisualizationFormat::Gif => {
+                fs::create_dir_all(&self.output_dir)?;
+                Box::new(Canvas::new(ImageWriter::Gif {
+                    path: self.output_dir.join(format!("{name}.gif")),
+                    delay: self.frame_delay,
+                }))
+            }
+        })
+    }
+}
+
+/// Draws frames on the terminal as they come, in place of the previous one.
+struct TerminalReplay {
+    delay: Duration,
+}
+
+impl FrameSink for TerminalReplay {
+    fn write_frame(&mut self, frame: &Frame) -> io::Result<()> {
+        let mut out = io::stdout().lock();
+        // Move to the top left corner and clear the screen.
+        write!(out, "\x1b[H\x1b[2J")?;
+        for row in frame.rows() {
+            for cell in row {
+                match cell.color {
+                    Some(color) => write!(out, "\x1b[{}m{}\x1b[0m", color.ansi_code(), cell.ch)?,
+                    None => write!(out, "{}", cell.ch)?,
+                }
+            }
+            writeln!(out)?;
+        }
+        out.flush()?;
+        thread::sleep(self.delay);
+        Ok(())
+    }
+
+    fn finish(self: Box<Self>) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+enum ImageWriter {
+    /// One `<name>-<frame number>.ppm` file per frame in `dir`.
+    Ppm {
+        dir: PathBuf,
+        name: String,
+    },
+    Gif {
+        path: PathBuf,
+        delay: Duration,
+    },
+}
+
+/// The area covered by a frame.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+struct Area {
+    left: i64,
+    top: i64,
+    width: usize,
+    height: usize,
+}
+
+/// A frame reduced to the palette indices of its cells, which is all that
+/// images need, to keep long recordings small.
+struct IndexedFrame {
+    area: Area,
+    indices: Vec<u8>,
+}
+
+impl IndexedFrame {
+    fn new(frame: &Frame) -> IndexedFrame {
+        IndexedFrame {
+            area: Area {
+                left: frame.left,
+                top: frame.top,
+                width: frame.width,
+                height: frame.height,
+            },
+            indices: frame
+                .cells
+                .iter()
+                .map(|cell| cell.palette_index())
+                .collect(),
+        }
+    }
+
+    fn get(&self, x: i64, y: i64) -> u8 {
+        let Area {
+            left,
+            top,
+            width,
+            height,
+        } = self.area;
+        let (x, y) = (x - left, y - top);
+        if x < 0 || y < 0 || x as usize >= width || y as usize >= height {
+            return BLACK;
+        }
+        self.indices[y as usize * width + x as usize]
+    }
+}
+
+/// Collects frames so that they can all be drawn at the same size and
+/// position, on a canvas that covers every one of them.
+struct Canvas {
+    writer: ImageWriter,
+    frames: Vec<IndexedFrame>,
+}
+
+impl Canvas {
+    fn new(writer: ImageWriter) -> Canvas {
+        Canvas {
+            writer,
+            frames: vec![],
+        }
+    }
+
+    /// Returns the area covered by all frames.
+    fn bounds(&self) -> Area {
+        let areas = || self.frames.iter().map(|f| f.area);
+        let left = areas().map(|a| a.left).min().unwrap_or(0);
+        let top = areas().map(|a| a.top).min().unwrap_or(0);
+        let right = areas().map(|a| a.left + a.width as i64).max().unwrap_or(0);
+        let bottom = areas().map(|a| a.top + a.height as i64).max().unwrap_or(0);
+        Area {
+            left,
+            top,
+            width: (right - left) as usize,
+            height: (bottom - top) as usize,
+        }
+    }
+
+    /// Returns the palette indices of the pixels of `frame` drawn on `bounds`.
+    fn pixels(bounds: Area, frame: &IndexedFrame) -> Vec<u8> {
+        let mut pixels =
+            Vec::with_capacity(bounds.width * bounds.height * CELL_PIXELS * CELL_PIXELS);
+        for y in 0..bounds.height as i64 {
+            let row: Vec<u8> = (0..bounds.width as i64)
+                .map(|x| frame.get(bounds.left + x, bounds.top + y))
+                .flat_map(|idx| [idx; CELL_PIXELS])
+                .collect();
+            for _ in 0..CELL_PIXELS {
+                pixels.extend_from_slice(&row);
+            }
+        }
+        pixels
+    }
+}
+
+impl FrameSink for Canvas {
+    fn write_frame(&mut self, frame: &Frame) -> io::Result<()> {
+        self.frames.push(IndexedFrame::new(frame));
+        Ok(())
+    }
+
+    fn finish(self: Box<Self>) -> io::Result<()> {
+        let bounds = self.bounds();
+        let (width, height) = (bounds.width * CELL_PIXELS, bounds.height * CELL_PIXELS);
+        let palette = palette();
+        match &self.writer {
+            ImageWriter::Ppm { dir, name } => {
+                for (idx, frame) in self.frames.iter().enumerate() {
+                    let path = dir.join(format!("{name}-{:05}.ppm", idx + 1));
+                    let mut out = BufWriter::new(File::create(path)?);
+                    write!(out, "P6\n{width} {height}\n255\n")?;
+                    for pixel in Canvas::pixels(bounds, frame) {
+                        out.write_all(&palette[pixel as usize])?;
+                    }
+                    out.flush()?;
+                }
+            }
+            ImageWriter::Gif { path, delay } => {
+                let (Ok(gif_width), Ok(gif_height)) = (u16::try_from(width), u16::try_from(height))
+                else {
+                    return Err(io::Error::other(format!(
+                        "{width}x{height} is too large for a GIF"
+                    )));
+                };
+                let flat_palette: Vec<u8> = palette.concat();
+                let mut encoder = gif::Encoder::new(
+                    BufWriter::new(File::create(path)?),
+                    gif_width,
+                    gif_height,
+                    &flat_palette,
+                )
+                .map_err(io::Error::other)?;
+                encoder
+                    .set_repeat(gif::Repeat::Infinite)
+                    .map_err(io::Error::other)?;
+                for frame in self.frames.iter() {
+                    let mut gif_frame = gif::Frame::from_indexed_pixels(
+                        gif_width,
+                        gif_height,
+                        Canvas::pixels(bounds, frame),
+                        None,
+                    );
+                    gif_frame.delay = (delay.as_millis() / 10).try_into().unwrap_or(u16::MAX);
+                    encoder.write_frame(&gif_frame).map_err(io::Error::other)?;
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn frame_coordinates() {
+        let mut frame = Frame::new(-2, 3, 4, 2);
+        frame.set(-1, 4, '#', Some(Color::Red));
+        frame.set(5, 5, '#', None);
+        assert_eq!(
+            frame.cells[frame.index(-1, 4).unwrap()],
+            Cell {
+                ch: '#',
+                color: Some(Color::Red)
+            }
+        );
+        assert_eq!(frame.index(5, 5), None);
+        assert_eq!(frame.cells.iter().filter(|c| **c == Cell::BLANK).count(), 7);
+        assert_eq!(frame.rows().count(), 2);
+    }
+
+    #[test]
+    fn canvas_covers_all_frames() {
+        let mut canvas = Canvas::new(ImageWriter::Ppm {
+            dir: PathBuf::new(),
+            name: String::new(),
+        });
+        canvas.write_frame(&Frame::new(0, 0, 2, 2)).unwrap();
+        canvas.write_frame(&Frame::new(-1, 1, 2, 3)).unwrap();
+        assert_eq!(
+            canvas.bounds(),
+            Area {
+                left: -1,
+                top: 0,
+                width: 3,
+                height: 4
+            }
+        );
+    }
+
+    #[test]
+    fn pixels_are_scaled_cells() {
+        let mut frame = Frame::new(0, 0, 1, 1);
+        frame.set(0, 0, 'E', Some(Color::Red));
+        let frame = IndexedFrame::new(&frame);
+        let pixels = Canvas::pixels(frame.area, &frame);
+        assert_eq!(pixels, vec![2; CELL_PIXELS * CELL_PIXELS]);
+    }
+}
@@ -0,0 +1,69 @@
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::thread;
+use std::time::Duration;
+
+/// Result of running a function under the watchdog.
+pub enum Watched<T> {
+    /// The function returned, or panicked with the given payload.
+    Finished(thread::Result<T>),
+    /// The function did not return in time. Threads cannot be killed, so it
+    /// keeps running in the background and its result is discarded.
+    TimedOut,
+}
+
+/// Runs `f` on a worker thread and waits at most `timeout` for it to finish.
+/// Without a timeout, `f` runs on the current thread.
+pub fn run<T, F>(timeout: Option<Duration>, f: F) -> Watched<T>
+where
+    T: Send + 'static,
+    F: FnOnce() -> T + Send + 'static,
+{
+    let Some(timeout) = timeout else {
+        return Watched::Finished(panic::catch_unwind(AssertUnwindSafe(f)));
+    };
+    let (sender, receiver) = mpsc::channel();
+    thread::spawn(move || {
+        // If the watchdog has given up on us, there is nobody to send to.
+        let _ = sender.send(panic::catch_unwind(AssertUnwindSafe(f)));
+    });
+    match receiver.recv_timeout(timeout) {
+        Ok(result) => Watched::Finished(result),
+        Err(RecvTimeoutError::Timeout) => Watched::TimedOut,
+        Err(RecvTimeoutError::Disconnected) => {
+            unreachable!("watchdog worker exited without sending a result")
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn finishes_in_time() {
+        match run(Some(Duration::from_secs(10)), || 42) {
+            Watched::Finished(Ok(v)) => assert_eq!(v, 42),
+            _ => panic!("expected the function to finish"),
+        }
+    }
+
+    #[test]
+    fn finishes_without_timeout() {
+        assert!(matches!(run(None, || 42), Watched::Finished(Ok(42))));
+    }
+
+    #[test]
+    fn times_out() {
+        let result = run(Some(Duration::from_millis(10)), || {
+            thread::sleep(Duration::from_secs(60));
+        });
+        assert!(matches!(result, Watched::TimedOut));
+    }
+
+    #[test]
+    fn reports_panics() {
+        let result = run(Some(Duration::from_secs(10)), || panic!("oops"));
+        assert!(matches!(result, Watched::Finished(Err(_))));
+    }
+}
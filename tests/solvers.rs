@@ -0,0 +1,83 @@
+use aoc18::context::Context;
+use aoc18::registry;
+use aoc18::solver::Answer;
+use aoc18::solver::set_params;
+
+#[test]
+fn registry_lists_every_day() {
+    let solvers = registry::solvers(2018);
+    assert_eq!(solvers.len(), 15);
+    for info in solvers {
+        assert!(!info.title.is_empty(), "day {} has no title", info.day);
+        assert!(registry::solver_for_day(2018, info.day).is_some());
+    }
+    assert!(registry::find(2018, 25).is_none());
+}
+
+#[test]
+fn solve_example_through_registry() {
+    let mut solver = registry::solver_for_day(2018, 2).unwrap();
+    solver
+        .presolve("abcde\nfghij\nklmno\npqrst\nfguij\naxcye\nwvxyz\n")
+        .unwrap();
+    assert_eq!(
+        solver.solve_part_two(&Context::default()),
+        Answer::Text("fgij".into())
+    );
+}
+
+#[test]
+fn parameters_change_the_answer() {
+    let mut solver = registry::solver_for_day(2018, 7).unwrap();
+    set_params(
+        solver.as_mut(),
+        &[("worker_count".into(), 2), ("work_time".into(), 0)],
+    )
+    .unwrap();
+    solver
+        .presolve(
+            "Step C must be finished before step A can begin.
+Step C must be finished before step F can begin.
+Step A must be finished before step B can begin.
+Step A must be finished before step D can begin.
+Step B must be finished before step E can begin.
+Step D must be finished before step E can begin.
+Step F must be finished before step E can begin.
+",
+        )
+        .unwrap();
+    assert_eq!(
+        solver.solve_part_two(&Context::default()),
+        Answer::Integer(15)
+    );
+    assert!(set_params(solver.as_mut(), &[("workers".into(), 2)]).is_err());
+}
+
+#[test]
+fn invalid_input_is_a_parse_error() {
+    let mut solver = registry::solver_for_day(2018, 1).unwrap();
+    let error = solver.presolve("+1\nplus two\n").unwrap_err();
+    assert_eq!((error.line, error.column), (2, 1));
+}
+
+#[test]
+fn explain_mode_records_a_trace() {
+    let mut solver = registry::solver_for_day(2018, 15).unwrap();
+    solver
+        .presolve(
+            "#######
+#.G...#
+#...EG#
+#.#.#G#
+#..G#E#
+#.....#
+#######
+",
+        )
+        .unwrap();
+    let ctx = Context::default().with_trace();
+    assert_eq!(solver.solve_part_one(&ctx), Answer::Integer(27730));
+    let trace = ctx.take_trace();
+    let outcome = trace.iter().find(|entry| entry.name == "outcome").unwrap();
+    assert_eq!(outcome.value.as_deref(), Some("27730"));
+}
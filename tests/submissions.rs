@@ -0,0 +1,106 @@
+use aoc18::aocclient::AocClient;
+use aoc18::aocclient::Error;
+use aoc18::aocclient::ValidationResult;
+use aoc18::autosubmit::SubmissionLog;
+use aoc18::solver::Answer;
+use httptest::Expectation;
+use httptest::Server;
+use httptest::matchers::all_of;
+use httptest::matchers::contains;
+use httptest::matchers::request;
+use httptest::matchers::url_decoded;
+use httptest::responders::status_code;
+
+fn client_for(server: &Server) -> AocClient {
+    let url = server.url("/");
+    let base_url = format!("{}://{}/", url.scheme().unwrap(), url.authority().unwrap());
+    let client = AocClient::new_with_base(&base_url).unwrap();
+    client.set_cookie("deadbeef");
+    client
+}
+
+#[test]
+fn download_input() {
+    let server = Server::run();
+    server.expect(
+        Expectation::matching(all_of![
+            request::method("GET"),
+            request::path("/2018/day/1/input"),
+            request::headers(contains(("cookie", "session=deadbeef"))),
+        ])
+        .respond_with(status_code(200).body("+1\n-2\n")),
+    );
+    assert_eq!(
+        client_for(&server).get_puzzle_input(2018, 1).unwrap(),
+        "+1\n-2\n"
+    );
+}
+
+#[test]
+fn wrong_answers_are_submitted_once() {
+    let server = Server::run();
+    server.expect(
+        Expectation::matching(all_of![
+            request::method("POST"),
+            request::path("/2018/day/3/answer"),
+            request::body(url_decoded(contains(("answer", "1234")))),
+        ])
+        .times(1)
+        .respond_with(
+            status_code(200).body("That's not the right answer; your answer is too high."),
+        ),
+    );
+    let client = client_for(&server);
+    let dir = tempfile::tempdir().unwrap();
+    let log = SubmissionLog::new(dir.path().join("results.toml"));
+    let submit = |answer: i64| {
+        log.submit_with_cache(2018, 3, 1, &Answer::Integer(answer), |y, d, l, a| {
+            client.submit_answer(y, d, l, a).unwrap()
+        })
+    };
+    assert_eq!(submit(1234), ValidationResult::RejectedTooHigh);
+    // Known to be too high without asking the server again.
+    assert_eq!(submit(1234), ValidationResult::RejectedTooHigh);
+    assert_eq!(submit(2000), ValidationResult::RejectedTooHigh);
+    let entry = log.puzzle_log_entry(2018, 3, 1).unwrap();
+    assert_eq!(entry.upper_bound, Some(1234));
+    assert_eq!(entry.accepted_answer, None);
+}
+
+#[test]
+fn accepted_answers_advance_the_next_unsolved_day() {
+    let server = Server::run();
+    server.expect(
+        Expectation::matching(request::method("POST"))
+            .times(2)
+            .respond_with(status_code(200).body("That's the right answer!")),
+    );
+    let client = client_for(&server);
+    let dir = tempfile::tempdir().unwrap();
+    let log = SubmissionLog::new(dir.path().join("results.toml"));
+    assert_eq!(log.next_unsolved_day(2018), 1);
+    for level in [1, 2] {
+        let result = log.submit_with_cache(2018, 1, level, &Answer::Integer(42), |y, d, l, a| {
+            client.submit_answer(y, d, l, a).unwrap()
+        });
+        assert_eq!(result, ValidationResult::Accepted);
+    }
+    assert_eq!(log.next_unsolved_day(2018), 2);
+}
+
+#[test]
+fn failed_submissions_are_not_recorded() {
+    let server = Server::run();
+    server.expect(
+        Expectation::matching(request::path("/2018/day/2/answer"))
+            .respond_with(status_code(400).body("Please log in.")),
+    );
+    let client = client_for(&server);
+    let dir = tempfile::tempdir().unwrap();
+    let log = SubmissionLog::new(dir.path().join("results.toml"));
+    let result = log.try_submit_with_cache(2018, 2, 1, &Answer::Integer(7), |y, d, l, a| {
+        client.submit_answer(y, d, l, a)
+    });
+    assert!(matches!(result, Err(Error::NotLoggedIn)));
+    assert!(log.puzzle_log_entry(2018, 2, 1).is_none());
+}